@@ -1,5 +1,8 @@
 use audio_converter::*;
-use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use audio_converter::audio::converter::ConvertedAudio;
+use audio_converter::audio::simd;
+use audio_converter::config::settings::OutputSettings;
+use criterion::{BatchSize, BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
 use tempfile::NamedTempFile;
 
 fn create_test_wav_file(sample_rate: u32, channels: u16, duration_seconds: f32) -> NamedTempFile {
@@ -175,12 +178,86 @@ fn bench_output_formats(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_sample_kernels(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sample_kernels");
+
+    let samples: Vec<f32> = (0..88200)
+        .map(|i| (i as f32 * 440.0 * 2.0 * std::f32::consts::PI / 44100.0).sin())
+        .collect();
+
+    // 向量化内核与标量参考实现对比
+    // 每次迭代使用新的副本，避免反复缩放后样本衰减为非规格化数
+    group.bench_function("gain/simd", |b| {
+        b.iter_batched_ref(
+            || samples.clone(),
+            |buffer| simd::scale(black_box(buffer), 0.999),
+            BatchSize::LargeInput,
+        );
+    });
+    group.bench_function("gain/scalar", |b| {
+        b.iter_batched_ref(
+            || samples.clone(),
+            |buffer| simd::scalar::scale(black_box(buffer), 0.999),
+            BatchSize::LargeInput,
+        );
+    });
+
+    group.bench_function("peak/simd", |b| b.iter(|| simd::peak(black_box(&samples))));
+    group.bench_function("peak/scalar", |b| b.iter(|| simd::scalar::peak(black_box(&samples))));
+
+    let mut mono = vec![0.0; samples.len() / 2];
+    group.bench_function("stereo_to_mono/simd", |b| {
+        b.iter(|| simd::stereo_to_mono(black_box(&samples), &mut mono));
+    });
+    group.bench_function("stereo_to_mono/scalar", |b| {
+        b.iter(|| simd::scalar::stereo_to_mono(black_box(&samples), &mut mono));
+    });
+
+    let mut quantized = vec![0; samples.len()];
+    group.bench_function("quantize_i16/simd", |b| {
+        b.iter(|| simd::quantize(black_box(&samples), 16, &mut quantized));
+    });
+    group.bench_function("quantize_i16/scalar", |b| {
+        b.iter(|| simd::scalar::quantize(black_box(&samples), 16, &mut quantized));
+    });
+
+    group.finish();
+}
+
+fn bench_array_rendering(c: &mut Criterion) {
+    let mut group = c.benchmark_group("array_rendering");
+
+    let samples: Vec<f32> = (0..88200)
+        .map(|i| (i as f32 / 88200.0) * 2.0 - 1.0)
+        .collect();
+    let settings = OutputSettings::default();
+
+    for format in [OutputFormat::F32, OutputFormat::I16, OutputFormat::I24Packed] {
+        let audio = ConvertedAudio {
+            samples: samples.clone(),
+            sample_rate: 44100,
+            channels: 2,
+            format,
+        };
+
+        group.bench_with_input(
+            BenchmarkId::new("render", format!("{:?}", format)),
+            &format,
+            |b, _| b.iter(|| ArrayWriter::render(black_box(&audio), &settings).unwrap()),
+        );
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_audio_decoding,
     bench_audio_conversion,
     bench_resampling,
     bench_channel_conversion,
-    bench_output_formats
+    bench_output_formats,
+    bench_sample_kernels,
+    bench_array_rendering
 );
 criterion_main!(benches);
@@ -0,0 +1,353 @@
+use serde::Serialize;
+use std::fmt;
+use crate::audio::filter::Biquad;
+use crate::audio::simd;
+
+/// 视为削波的幅度阈值
+const CLIP_THRESHOLD: f32 = 0.999;
+/// 静音判定阈值 (dBFS)
+const SILENCE_THRESHOLD_DB: f64 = -60.0;
+/// 静音比例与噪底估计所用的分析窗口长度 (秒)
+const WINDOW_SECONDS: f64 = 0.02;
+/// 噪底取窗口 RMS 的该百分位
+const NOISE_FLOOR_PERCENTILE: f64 = 0.1;
+/// dBFS 下限，静音或空输入的电平钳位到该值而不是 -inf
+pub const DBFS_FLOOR: f64 = -120.0;
+
+/// 单个声道的统计信息
+#[derive(Serialize, Debug, Clone)]
+pub struct ChannelStats {
+    pub peak: f32,
+    pub peak_dbfs: f64,
+    pub rms: f32,
+    pub rms_dbfs: f64,
+    pub dc_offset: f32,
+    pub crest_factor_db: f64,
+    pub clipped_samples: usize,
+    /// 低于静音阈值的窗口所占比例 (0.0 - 1.0)
+    pub silence_ratio: f64,
+    pub noise_floor_dbfs: Option<f64>,
+}
+
+/// 音频分析结果
+#[derive(Serialize, Debug, Clone)]
+pub struct AudioAnalysis {
+    pub sample_rate: u32,
+    pub channels: u32,
+    pub frames: usize,
+    pub duration_seconds: f64,
+    /// ITU-R BS.1770 积分响度，音频短于一个测量块 (400ms) 时为 None
+    pub integrated_loudness_lufs: Option<f64>,
+    pub channel_stats: Vec<ChannelStats>,
+}
+
+/// 分析交错排列的样本
+pub fn analyze(samples: &[f32], sample_rate: u32, channels: u32) -> AudioAnalysis {
+    let channel_count = channels.max(1) as usize;
+    let frames = samples.len() / channel_count;
+
+    let channel_stats = (0..channel_count)
+        .map(|ch| {
+            let channel: Vec<f32> = samples
+                .iter()
+                .skip(ch)
+                .step_by(channel_count)
+                .copied()
+                .collect();
+            channel_stats(&channel, sample_rate)
+        })
+        .collect();
+
+    AudioAnalysis {
+        sample_rate,
+        channels,
+        frames,
+        duration_seconds: if sample_rate > 0 { frames as f64 / sample_rate as f64 } else { 0.0 },
+        integrated_loudness_lufs: integrated_loudness(samples, sample_rate, channel_count),
+        channel_stats,
+    }
+}
+
+/// 样本绝对值的最大值
+pub fn peak(samples: &[f32]) -> f32 {
+    simd::peak(samples)
+}
+
+/// 均方根值
+pub fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum: f64 = samples.iter().map(|&s| (s as f64) * (s as f64)).sum();
+    (sum / samples.len() as f64).sqrt() as f32
+}
+
+/// 线性幅度转换为 dBFS，结果不低于 [`DBFS_FLOOR`]
+pub fn to_dbfs(value: f64) -> f64 {
+    (20.0 * value.log10()).max(DBFS_FLOOR)
+}
+
+fn channel_stats(channel: &[f32], sample_rate: u32) -> ChannelStats {
+    let channel_peak = peak(channel);
+    let channel_rms = rms(channel);
+    let dc_offset = if channel.is_empty() {
+        0.0
+    } else {
+        (channel.iter().map(|&s| s as f64).sum::<f64>() / channel.len() as f64) as f32
+    };
+    let crest_factor_db = if channel_rms > 0.0 {
+        to_dbfs(channel_peak as f64 / channel_rms as f64)
+    } else {
+        0.0
+    };
+    let clipped_samples = channel.iter().filter(|s| s.abs() >= CLIP_THRESHOLD).count();
+
+    // 按固定窗口计算 RMS，用于静音比例和噪底估计
+    let window = ((sample_rate as f64 * WINDOW_SECONDS) as usize).max(1);
+    let mut window_levels: Vec<f64> = channel
+        .chunks(window)
+        .map(|chunk| to_dbfs(rms(chunk) as f64))
+        .collect();
+
+    let silence_ratio = if window_levels.is_empty() {
+        0.0
+    } else {
+        window_levels.iter().filter(|&&db| db < SILENCE_THRESHOLD_DB).count() as f64
+            / window_levels.len() as f64
+    };
+
+    window_levels.sort_by(|a, b| a.total_cmp(b));
+    let noise_floor_dbfs = if window_levels.is_empty() {
+        None
+    } else {
+        let index = ((window_levels.len() - 1) as f64 * NOISE_FLOOR_PERCENTILE) as usize;
+        Some(window_levels[index])
+    };
+
+    ChannelStats {
+        peak: channel_peak,
+        peak_dbfs: to_dbfs(channel_peak as f64),
+        rms: channel_rms,
+        rms_dbfs: to_dbfs(channel_rms as f64),
+        dc_offset,
+        crest_factor_db,
+        clipped_samples,
+        silence_ratio,
+        noise_floor_dbfs,
+    }
+}
+
+/// ITU-R BS.1770-4 积分响度（K 加权 + 绝对/相对门限）
+fn integrated_loudness(samples: &[f32], sample_rate: u32, channels: usize) -> Option<f64> {
+    if sample_rate == 0 {
+        return None;
+    }
+
+    let block = (sample_rate as f64 * 0.4) as usize;
+    let step = (sample_rate as f64 * 0.1) as usize;
+    let frames = samples.len() / channels;
+    if block == 0 || step == 0 || frames < block {
+        return None;
+    }
+
+    // 每个声道独立进行 K 加权滤波
+    let mut weighted = vec![0.0_f64; frames * channels];
+    for ch in 0..channels {
+        let mut filter = KWeighting::new(sample_rate as f64);
+        for frame in 0..frames {
+            let index = frame * channels + ch;
+            weighted[index] = filter.process(samples[index] as f64);
+        }
+    }
+
+    // 5.1 声道时 LFE 不计入，环绕声道加权 1.41
+    let weights: Vec<f64> = (0..channels)
+        .map(|ch| match (channels, ch) {
+            (6, 3) => 0.0,
+            (6, 4) | (6, 5) => 1.41,
+            _ => 1.0,
+        })
+        .collect();
+
+    let block_powers: Vec<f64> = (0..=(frames - block) / step)
+        .map(|i| {
+            let start = i * step;
+            (0..channels)
+                .map(|ch| {
+                    let sum: f64 = (start..start + block)
+                        .map(|frame| weighted[frame * channels + ch].powi(2))
+                        .sum();
+                    weights[ch] * sum / block as f64
+                })
+                .sum()
+        })
+        .collect();
+
+    let loudness = |power: f64| -0.691 + 10.0 * power.log10();
+    let gated_mean = |threshold: f64| {
+        let gated: Vec<f64> = block_powers
+            .iter()
+            .copied()
+            .filter(|&p| loudness(p) > threshold)
+            .collect();
+        if gated.is_empty() {
+            None
+        } else {
+            Some(gated.iter().sum::<f64>() / gated.len() as f64)
+        }
+    };
+
+    let absolute_mean = gated_mean(-70.0)?;
+    let relative_threshold = loudness(absolute_mean) - 10.0;
+    gated_mean(relative_threshold).map(loudness)
+}
+
+/// BS.1770 K 加权滤波器：高架滤波 + RLB 高通滤波
+struct KWeighting {
+    shelf: Biquad,
+    highpass: Biquad,
+}
+
+impl KWeighting {
+    fn new(sample_rate: f64) -> Self {
+        // 系数按任意采样率重新计算（参考 libebur128）
+        let f0 = 1681.974450955533;
+        let gain = 3.999843853973347;
+        let q = 0.7071752369554196;
+        let k = (std::f64::consts::PI * f0 / sample_rate).tan();
+        let vh = 10.0_f64.powf(gain / 20.0);
+        let vb = vh.powf(0.4996667741545416);
+        let a0 = 1.0 + k / q + k * k;
+        let shelf = Biquad::from_coefficients(
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+            2.0 * (k * k - 1.0) / a0,
+            (1.0 - k / q + k * k) / a0,
+        );
+
+        let f0 = 38.13547087602444;
+        let q = 0.5003270373238773;
+        let k = (std::f64::consts::PI * f0 / sample_rate).tan();
+        let a0 = 1.0 + k / q + k * k;
+        let highpass = Biquad::from_coefficients(
+            1.0,
+            -2.0,
+            1.0,
+            2.0 * (k * k - 1.0) / a0,
+            (1.0 - k / q + k * k) / a0,
+        );
+
+        Self { shelf, highpass }
+    }
+
+    fn process(&mut self, input: f64) -> f64 {
+        self.highpass.process(self.shelf.process(input))
+    }
+}
+
+impl fmt::Display for AudioAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "采样率: {}Hz", self.sample_rate)?;
+        writeln!(f, "声道数: {}", self.channels)?;
+        writeln!(f, "帧数: {}", self.frames)?;
+        writeln!(f, "时长: {:.3} 秒", self.duration_seconds)?;
+        match self.integrated_loudness_lufs {
+            Some(lufs) => writeln!(f, "积分响度: {:.1} LUFS", lufs)?,
+            None => writeln!(f, "积分响度: N/A")?,
+        }
+
+        for (ch, stats) in self.channel_stats.iter().enumerate() {
+            writeln!(f, "声道 {}:", ch)?;
+            writeln!(f, "  峰值: {:.6} ({:.2} dBFS)", stats.peak, stats.peak_dbfs)?;
+            writeln!(f, "  RMS: {:.6} ({:.2} dBFS)", stats.rms, stats.rms_dbfs)?;
+            writeln!(f, "  直流偏移: {:.6}", stats.dc_offset)?;
+            writeln!(f, "  波峰因数: {:.2} dB", stats.crest_factor_db)?;
+            writeln!(f, "  削波样本数: {}", stats.clipped_samples)?;
+            writeln!(f, "  静音比例: {:.1}%", stats.silence_ratio * 100.0)?;
+            match stats.noise_floor_dbfs {
+                Some(db) => writeln!(f, "  噪底估计: {:.2} dBFS", db)?,
+                None => writeln!(f, "  噪底估计: N/A")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f32, amplitude: f32, sample_rate: u32, seconds: f32) -> Vec<f32> {
+        (0..(sample_rate as f32 * seconds) as usize)
+            .map(|i| amplitude * (i as f32 * frequency * 2.0 * std::f32::consts::PI / sample_rate as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_peak_and_rms() {
+        let samples = vec![0.5, -0.8, 0.2, 0.0];
+        assert!((peak(&samples) - 0.8).abs() < 1e-6);
+        assert!((rms(&samples) - ((0.25 + 0.64 + 0.04) / 4.0_f32).sqrt()).abs() < 1e-6);
+        assert_eq!(peak(&[]), 0.0);
+        assert_eq!(rms(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_sine_statistics() {
+        let samples = sine(1000.0, 0.5, 48000, 1.0);
+        let analysis = analyze(&samples, 48000, 1);
+        let stats = &analysis.channel_stats[0];
+
+        assert_eq!(analysis.frames, 48000);
+        assert!((stats.peak - 0.5).abs() < 1e-3);
+        assert!((stats.rms - 0.5 / 2.0_f32.sqrt()).abs() < 1e-3);
+        // 正弦波的波峰因数约为 3.01 dB
+        assert!((stats.crest_factor_db - 3.01).abs() < 0.05);
+        assert!(stats.dc_offset.abs() < 1e-3);
+        assert_eq!(stats.clipped_samples, 0);
+        assert_eq!(stats.silence_ratio, 0.0);
+    }
+
+    #[test]
+    fn test_integrated_loudness_reference_tone() {
+        // BS.1770 参考：1kHz、-20 dBFS 的单声道正弦波约为 -23 LUFS
+        let amplitude = 10.0_f32.powf(-20.0 / 20.0);
+        let samples = sine(1000.0, amplitude, 48000, 2.0);
+        let analysis = analyze(&samples, 48000, 1);
+
+        let lufs = analysis.integrated_loudness_lufs.unwrap();
+        assert!((lufs - (-23.0)).abs() < 0.2, "lufs = {}", lufs);
+    }
+
+    #[test]
+    fn test_stereo_channels_are_separated() {
+        let samples: Vec<f32> = (0..4800).flat_map(|_| [0.25, 1.0]).collect();
+        let analysis = analyze(&samples, 48000, 2);
+
+        assert_eq!(analysis.channel_stats.len(), 2);
+        assert!((analysis.channel_stats[0].dc_offset - 0.25).abs() < 1e-6);
+        assert_eq!(analysis.channel_stats[0].clipped_samples, 0);
+        assert_eq!(analysis.channel_stats[1].clipped_samples, 4800);
+    }
+
+    #[test]
+    fn test_silence_and_short_input() {
+        let samples = vec![0.0; 1000];
+        let analysis = analyze(&samples, 48000, 1);
+
+        assert_eq!(analysis.channel_stats[0].silence_ratio, 1.0);
+        assert!(analysis.integrated_loudness_lufs.is_none());
+
+        // 静音电平钳位到下限，而不是 -inf
+        let stats = &analysis.channel_stats[0];
+        assert_eq!(stats.peak_dbfs, DBFS_FLOOR);
+        assert_eq!(stats.rms_dbfs, DBFS_FLOOR);
+        assert_eq!(stats.noise_floor_dbfs, Some(DBFS_FLOOR));
+
+        let empty = analyze(&[], 48000, 1);
+        assert_eq!(empty.channel_stats[0].peak_dbfs, DBFS_FLOOR);
+        assert!(empty.channel_stats[0].rms_dbfs.is_finite());
+    }
+}
@@ -1,113 +1,100 @@
+use crate::audio::pipeline::{self, AudioBuffer, ChannelUsage, ProcessingStage};
 use crate::cli::args::OutputFormat;
 use crate::config::settings::Config;
 use anyhow::Result;
 
 pub struct AudioConverter {
     config: Config,
+    stages: Vec<Box<dyn ProcessingStage>>,
 }
 
 impl AudioConverter {
     pub fn new(config: Config) -> Self {
-        Self { config }
+        let stages = pipeline::build_stages(&config);
+        Self { config, stages }
     }
 
-    pub fn convert(&self, samples: &[f32], sample_rate: u32, channels: u32) -> Result<ConvertedAudio> {
-        let mut processed_samples = samples.to_vec();
-
-        // 应用增益
-        if self.config.gain != 0.0 {
-            let gain_factor = 10.0_f32.powf(self.config.gain / 20.0);
-            for sample in processed_samples.iter_mut() {
-                *sample *= gain_factor;
-            }
-        }
+    /// 使用自定义的处理链创建转换器（忽略配置中的处理步骤）
+    pub fn with_stages(config: Config, stages: Vec<Box<dyn ProcessingStage>>) -> Self {
+        Self { config, stages }
+    }
 
-        // 重采样 (简化实现)
-        if let Some(target_sr) = self.config.sample_rate {
-            if target_sr != sample_rate {
-                processed_samples = self.resample(&processed_samples, sample_rate, target_sr)?;
-            }
-        }
+    /// 在处理链末尾追加一个步骤
+    pub fn push_stage(&mut self, stage: Box<dyn ProcessingStage>) {
+        self.stages.push(stage);
+    }
 
-        // 声道转换
-        if let Some(target_channels) = self.config.channels {
-            if target_channels != channels {
-                processed_samples = self.convert_channels(&processed_samples, channels, target_channels)?;
-            }
-        }
+    /// 在处理链的指定位置插入一个步骤
+    pub fn insert_stage(&mut self, index: usize, stage: Box<dyn ProcessingStage>) {
+        self.stages.insert(index, stage);
+    }
 
-        // 归一化
-        if self.config.normalize {
-            self.normalize_samples(&mut processed_samples);
-        }
+    /// 按执行顺序返回各步骤名称
+    pub fn stage_names(&self) -> Vec<&str> {
+        self.stages.iter().map(|s| s.name()).collect()
+    }
 
-        Ok(ConvertedAudio {
-            samples: processed_samples,
-            sample_rate: self.config.sample_rate.unwrap_or(sample_rate),
-            channels: self.config.channels.unwrap_or(channels),
-            format: self.config.output_format.clone(),
-        })
+    /// 处理链对指定采样率和声道数的输入不做任何改动（包括空处理链）
+    pub fn is_identity(&self, sample_rate: u32, channels: u32) -> bool {
+        self.stages.iter().all(|s| s.is_identity(sample_rate, channels))
     }
 
-    fn resample(&self, samples: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32>> {
-        // 简化的线性插值重采样
-        let ratio = to_rate as f64 / from_rate as f64;
-        let new_length = (samples.len() as f64 * ratio) as usize;
-        let mut resampled = Vec::with_capacity(new_length);
-
-        for i in 0..new_length {
-            let pos = i as f64 / ratio;
-            let index = pos as usize;
-            
-            if index + 1 < samples.len() {
-                let frac = pos - index as f64;
-                let sample = samples[index] * (1.0 - frac as f32) + samples[index + 1] * frac as f32;
-                resampled.push(sample);
-            } else if index < samples.len() {
-                resampled.push(samples[index]);
+    /// 处理链最多读取的输入声道数，更多的声道可以在解码时丢弃；全部声道都会用到时返回 `None`
+    ///
+    /// 只有排在限制声道的步骤之前、并且逐声道独立处理的步骤不受丢弃影响。
+    pub fn max_input_channels(&self) -> Option<u32> {
+        for stage in &self.stages {
+            match stage.channel_usage() {
+                ChannelUsage::Independent => continue,
+                ChannelUsage::First(channels) => return Some(channels),
+                ChannelUsage::All => return None,
             }
         }
+        None
+    }
 
-        Ok(resampled)
+    /// 输出数组格式，`convert_in_place` 的调用方用它构造 `ConvertedAudio`
+    pub fn output_format(&self) -> OutputFormat {
+        self.config.output_format
     }
 
-    fn convert_channels(&self, samples: &[f32], from_channels: u32, to_channels: u32) -> Result<Vec<f32>> {
-        match (from_channels, to_channels) {
-            (2, 1) => {
-                // 立体声转单声道
-                let mut mono = Vec::with_capacity(samples.len() / 2);
-                for chunk in samples.chunks_exact(2) {
-                    mono.push((chunk[0] + chunk[1]) / 2.0);
-                }
-                Ok(mono)
-            }
-            (1, 2) => {
-                // 单声道转立体声
-                let mut stereo = Vec::with_capacity(samples.len() * 2);
-                for &sample in samples {
-                    stereo.push(sample);
-                    stereo.push(sample);
-                }
-                Ok(stereo)
-            }
-            _ => Ok(samples.to_vec()),
-        }
+    pub fn convert(&self, samples: &[f32], sample_rate: u32, channels: u32) -> Result<ConvertedAudio> {
+        let mut buffer = AudioBuffer {
+            samples: samples.to_vec(),
+            sample_rate,
+            channels,
+        };
+        self.convert_in_place(&mut buffer, &mut ConvertState::default())?;
+
+        Ok(ConvertedAudio {
+            samples: buffer.samples,
+            sample_rate: buffer.sample_rate,
+            channels: buffer.channels,
+            format: self.config.output_format,
+        })
     }
 
-    fn normalize_samples(&self, samples: &mut [f32]) {
-        if let Some(max_val) = samples.iter().map(|s| s.abs()).max_by(|a, b| a.partial_cmp(b).unwrap()) {
-            if max_val > 0.0 {
-                let scale = 1.0 / max_val;
-                for sample in samples.iter_mut() {
-                    *sample *= scale;
-                }
-            }
+    /// 原地转换：`buffer` 中的样本、采样率和声道数被替换为处理结果
+    ///
+    /// 改变样本数的步骤在 `buffer` 与 `state` 的临时缓冲区之间交替写入，
+    /// 调用方复用同一个 `buffer` 和 `state` 时，预热后转换不超过此前长度的音频不再分配内存。
+    pub fn convert_in_place(&self, buffer: &mut AudioBuffer, state: &mut ConvertState) -> Result<()> {
+        for stage in &self.stages {
+            stage.process_with(buffer, &mut state.scratch)?;
         }
+        Ok(())
     }
 }
 
-pub struct ConvertedAudio {
-    pub samples: Vec<f32>,
+/// `convert_in_place` 的可复用状态
+#[derive(Debug, Default)]
+pub struct ConvertState {
+    scratch: Vec<f32>,
+}
+
+/// 转换结果；整数直通时 `S` 为源文件的整数样本类型（见 `sample_format::Sample`）
+pub struct ConvertedAudio<S = f32> {
+    pub samples: Vec<S>,
     pub sample_rate: u32,
     pub channels: u32,
     pub format: OutputFormat,
@@ -116,6 +103,7 @@ pub struct ConvertedAudio {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::audio::pipeline::ProcessingStep;
     use crate::cli::args::OutputFormat;
     use crate::config::settings::Config;
 
@@ -126,11 +114,11 @@ mod tests {
             channels: None,
             gain: 0.0,
             normalize: false,
-            output_settings: crate::config::settings::OutputSettings {
-                array_type: "Vec".to_string(),
-                include_metadata: true,
-                compress: false,
-            },
+            filters: Vec::new(),
+            processing_chain: None,
+            features: None,
+            output_settings: crate::config::settings::OutputSettings::default(),
+            presets: Default::default(),
         }
     }
 
@@ -245,6 +233,154 @@ mod tests {
         assert!((max_val - 1.0).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_convert_with_filters() {
+        let mut config = create_test_config();
+        config.filters = vec!["dcblock".parse().unwrap()];
+        let converter = AudioConverter::new(config);
+
+        let test_samples = vec![0.5; 44100];
+        let converted = converter.convert(&test_samples, 44100, 1).unwrap();
+
+        assert!(converted.samples.last().unwrap().abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_convert_with_invalid_filter() {
+        let mut config = create_test_config();
+        config.filters = vec!["lowpass:30000".parse().unwrap()];
+        let converter = AudioConverter::new(config);
+
+        assert!(converter.convert(&[0.1, 0.2], 44100, 1).is_err());
+    }
+
+    #[test]
+    fn test_convert_with_processing_chain() {
+        // 先归一化再增益，增益不再被归一化抵消
+        let mut config = create_test_config();
+        config.processing_chain = Some(vec![
+            ProcessingStep::Channels { channels: Some(1) },
+            ProcessingStep::Normalize,
+            ProcessingStep::Gain { db: Some(-6.0) },
+        ]);
+        let converter = AudioConverter::new(config);
+        assert_eq!(converter.stage_names(), vec!["channels", "normalize", "gain"]);
+
+        let converted = converter.convert(&[0.1, 0.3, 0.2, 0.2], 44100, 2).unwrap();
+        assert_eq!(converted.channels, 1);
+        let expected = 10.0_f32.powf(-6.0 / 20.0);
+        assert!((converted.samples[0] - expected).abs() < 1e-6);
+        assert!((converted.samples[1] - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_convert_in_place_matches_convert() {
+        let mut config = create_test_config();
+        config.sample_rate = Some(22050);
+        config.channels = Some(1);
+        config.gain = -3.0;
+        config.normalize = true;
+        config.filters = vec!["highpass:100".parse().unwrap()];
+        let converter = AudioConverter::new(config);
+
+        let mut state = ConvertState::default();
+        let mut buffer = AudioBuffer {
+            samples: Vec::new(),
+            sample_rate: 0,
+            channels: 0,
+        };
+        // 长度不同的输入反复复用同一组缓冲区
+        for len in [4410, 882, 4410] {
+            let input: Vec<f32> = (0..len).map(|i| (i as f32 * 0.05).sin() * 0.5).collect();
+            let expected = converter.convert(&input, 44100, 2).unwrap();
+
+            buffer.samples.clear();
+            buffer.samples.extend_from_slice(&input);
+            buffer.sample_rate = 44100;
+            buffer.channels = 2;
+            converter.convert_in_place(&mut buffer, &mut state).unwrap();
+            assert_eq!(buffer.samples, expected.samples);
+            assert_eq!((buffer.sample_rate, buffer.channels), (22050, 1));
+        }
+    }
+
+    #[test]
+    fn test_is_identity() {
+        let mut config = create_test_config();
+        config.sample_rate = Some(44100);
+        config.channels = Some(2);
+        let converter = AudioConverter::new(config.clone());
+        assert!(converter.is_identity(44100, 2));
+        assert!(!converter.is_identity(48000, 2));
+        assert!(!converter.is_identity(44100, 1));
+
+        config.gain = 1.0;
+        assert!(!AudioConverter::new(config).is_identity(44100, 2));
+        assert!(AudioConverter::new(create_test_config()).is_identity(8000, 6));
+    }
+
+    #[test]
+    fn test_max_input_channels() {
+        let mut config = create_test_config();
+        config.channels = Some(2);
+        config.gain = -6.0;
+        assert_eq!(AudioConverter::new(config.clone()).max_input_channels(), Some(2));
+
+        // 处理链中没有声道转换时保留全部声道
+        config.processing_chain = Some(vec![ProcessingStep::Gain { db: None }]);
+        assert_eq!(AudioConverter::new(config.clone()).max_input_channels(), None);
+
+        // 重采样把交错样本作为一个整体插值，之后的声道转换不能提前丢弃声道
+        config.processing_chain = None;
+        config.sample_rate = Some(22050);
+        assert_eq!(AudioConverter::new(config).max_input_channels(), None);
+    }
+
+    #[test]
+    fn test_convert_drops_extra_channels() {
+        // 6 声道：每帧的第 n 个声道值为 n / 10
+        let samples: Vec<f32> = (0..60).map(|i| (i % 6) as f32 / 10.0).collect();
+        let mut config = create_test_config();
+
+        config.channels = Some(1);
+        let mono = AudioConverter::new(config.clone()).convert(&samples, 44100, 6).unwrap();
+        assert_eq!(mono.channels, 1);
+        assert_eq!(mono.samples.len(), 10);
+        assert!(mono.samples.iter().all(|&s| (s - 0.05).abs() < 1e-6));
+
+        config.channels = Some(4);
+        let quad = AudioConverter::new(config).convert(&samples, 44100, 6).unwrap();
+        assert_eq!(quad.channels, 4);
+        assert_eq!(&quad.samples[..8], &[0.0, 0.1, 0.2, 0.3, 0.0, 0.1, 0.2, 0.3]);
+    }
+
+    struct InvertStage;
+
+    impl ProcessingStage for InvertStage {
+        fn name(&self) -> &str {
+            "invert"
+        }
+
+        fn process(&self, buffer: &mut AudioBuffer) -> Result<()> {
+            for sample in buffer.samples.iter_mut() {
+                *sample = -*sample;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_convert_with_custom_stage() {
+        let mut config = create_test_config();
+        config.channels = Some(1);
+        let mut converter = AudioConverter::new(config);
+        converter.insert_stage(0, Box::new(InvertStage));
+        assert_eq!(converter.stage_names(), vec!["invert", "channels"]);
+
+        let converted = converter.convert(&[0.1, 0.3], 44100, 2).unwrap();
+        assert!((converted.samples[0] + 0.2).abs() < 1e-6);
+    }
+
     #[test]
     fn test_convert_empty_samples() {
         let config = create_test_config();
@@ -1,16 +1,77 @@
-use symphonia::core::audio::SampleBuffer;
+use symphonia::core::audio::{AudioBufferRef, SampleBuffer};
 use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
-use symphonia::core::formats::FormatOptions;
-use symphonia::core::io::MediaSourceStream;
-use symphonia::core::meta::MetadataOptions;
-use symphonia::core::probe::Hint;
-use std::fs::File;
+use symphonia::core::conv::ConvertibleSample;
+use crate::audio::probe::open_format;
+use crate::output::sample_format::Sample;
 use anyhow::Result;
 
+/// 解码选项
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DecodeOptions {
+    /// 源为整数 PCM 时保留原始整数样本，不转换为浮点
+    pub native: bool,
+    /// 最多保留的声道数，其余声道在解码时直接丢弃
+    pub max_channels: Option<u32>,
+    /// 不在标准输出打印解码完成信息
+    pub quiet: bool,
+}
+
+/// 按源位宽保存的整数样本（交错排列，满量程为类型的全部位数）
+///
+/// 8 位为 `i8`（无符号源转换为有符号），16 位为 `i16`，24 位左移 8 位后与 32 位一样为 `i32`。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NativeSamples {
+    I8(Vec<i8>),
+    I16(Vec<i16>),
+    I32(Vec<i32>),
+}
+
+impl NativeSamples {
+    pub fn len(&self) -> usize {
+        match self {
+            NativeSamples::I8(samples) => samples.len(),
+            NativeSamples::I16(samples) => samples.len(),
+            NativeSamples::I32(samples) => samples.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 每个样本的位数
+    pub fn bits(&self) -> u32 {
+        match self {
+            NativeSamples::I8(_) => 8,
+            NativeSamples::I16(_) => 16,
+            NativeSamples::I32(_) => 32,
+        }
+    }
+
+    /// 还原为浮点样本
+    pub fn to_f32(&self) -> Vec<f32> {
+        fn convert<S: Sample>(samples: &[S]) -> Vec<f32> {
+            samples.iter().map(|&s| s.to_f32()).collect()
+        }
+        match self {
+            NativeSamples::I8(samples) => convert(samples),
+            NativeSamples::I16(samples) => convert(samples),
+            NativeSamples::I32(samples) => convert(samples),
+        }
+    }
+}
+
 pub struct AudioDecoder {
     sample_rate: u32,
     channels: u32,
     samples: Vec<f32>,
+    native: Option<NativeSamples>,
+}
+
+impl Default for AudioDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AudioDecoder {
@@ -19,28 +80,18 @@ impl AudioDecoder {
             sample_rate: 0,
             channels: 0,
             samples: Vec::new(),
+            native: None,
         }
     }
 
     pub fn decode_file(&mut self, path: &str) -> Result<()> {
-        // 打开文件
-        let file = File::open(path)?;
-        let mss = MediaSourceStream::new(Box::new(file), Default::default());
-
-        // 创建格式提示
-        let mut hint = Hint::new();
-        if let Some(extension) = std::path::Path::new(path).extension() {
-            if let Some(ext_str) = extension.to_str() {
-                hint.with_extension(ext_str);
-            }
-        }
-
-        // 探测格式
-        let meta_opts: MetadataOptions = Default::default();
-        let fmt_opts: FormatOptions = Default::default();
+        self.decode_file_with(path, &DecodeOptions::default())
+    }
 
-        let probed = symphonia::default::get_probe()
-            .format(&hint, mss, &fmt_opts, &meta_opts)?;
+    /// 按选项解码；以整数形式解码时样本只保存在 `native_samples` 中，`get_samples` 为空
+    pub fn decode_file_with(&mut self, path: &str, options: &DecodeOptions) -> Result<()> {
+        // 打开文件并探测格式
+        let probed = open_format(path)?;
 
         let mut format = probed.format;
 
@@ -60,35 +111,46 @@ impl AudioDecoder {
         let mut decoder = symphonia::default::get_codecs()
             .make(&codec_params, &dec_opts)?;
 
-        // 获取音频参数
+        // 获取音频参数，多余的声道不保留
+        let source_channels = codec_params.channels.unwrap().count();
+        let keep = options
+            .max_channels
+            .map_or(source_channels, |max| source_channels.min(max.max(1) as usize));
         self.sample_rate = codec_params.sample_rate.unwrap_or(44100);
-        self.channels = codec_params.channels.unwrap().count() as u32;
+        self.channels = keep as u32;
 
         // 解码音频数据
-        let mut sample_buf = None;
-
-        loop {
-            let packet = match format.next_packet() {
-                Ok(packet) => packet,
-                Err(_) => break,
-            };
+        let mut float_buf = None;
+        let (mut i8_buf, mut i16_buf, mut i32_buf) = (None, None, None);
+        let mut native: Option<NativeSamples> = None;
+        let mut first = true;
 
+        while let Ok(packet) = format.next_packet() {
             if packet.track_id() != track_id {
                 continue;
             }
 
             match decoder.decode(&packet) {
                 Ok(audio_buf) => {
-                    // 转换为样本缓冲区
-                    if sample_buf.is_none() {
-                        let spec = *audio_buf.spec();
-                        let duration = audio_buf.capacity() as u64;
-                        sample_buf = Some(SampleBuffer::<f32>::new(duration, spec));
+                    // 第一个缓冲区决定是否按整数解码以及整数的位宽
+                    if first && options.native {
+                        native = match audio_buf {
+                            AudioBufferRef::U8(_) | AudioBufferRef::S8(_) => Some(NativeSamples::I8(Vec::new())),
+                            AudioBufferRef::U16(_) | AudioBufferRef::S16(_) => Some(NativeSamples::I16(Vec::new())),
+                            AudioBufferRef::U24(_)
+                            | AudioBufferRef::S24(_)
+                            | AudioBufferRef::U32(_)
+                            | AudioBufferRef::S32(_) => Some(NativeSamples::I32(Vec::new())),
+                            AudioBufferRef::F32(_) | AudioBufferRef::F64(_) => None,
+                        };
                     }
+                    first = false;
 
-                    if let Some(buf) = &mut sample_buf {
-                        buf.copy_interleaved_ref(audio_buf);
-                        self.samples.extend_from_slice(buf.samples());
+                    match &mut native {
+                        Some(NativeSamples::I8(samples)) => append(&mut i8_buf, samples, audio_buf, keep),
+                        Some(NativeSamples::I16(samples)) => append(&mut i16_buf, samples, audio_buf, keep),
+                        Some(NativeSamples::I32(samples)) => append(&mut i32_buf, samples, audio_buf, keep),
+                        None => append(&mut float_buf, &mut self.samples, audio_buf, keep),
                     }
                 }
                 Err(e) => {
@@ -98,7 +160,12 @@ impl AudioDecoder {
             }
         }
 
-        println!("解码完成: {} 个样本", self.samples.len());
+        if !options.quiet {
+            let count = native.as_ref().map_or(self.samples.len(), NativeSamples::len);
+            println!("解码完成: {} 个样本", count);
+        }
+
+        self.native = native;
         Ok(())
     }
 
@@ -106,6 +173,16 @@ impl AudioDecoder {
         &self.samples
     }
 
+    /// 以整数形式解码时的原始样本
+    pub fn native_samples(&self) -> Option<&NativeSamples> {
+        self.native.as_ref()
+    }
+
+    /// 取出整数样本，之后 `native_samples` 返回 `None`
+    pub fn take_native_samples(&mut self) -> Option<NativeSamples> {
+        self.native.take()
+    }
+
     pub fn get_sample_rate(&self) -> u32 {
         self.sample_rate
     }
@@ -115,6 +192,28 @@ impl AudioDecoder {
     }
 }
 
+/// 把解码出的缓冲区转换为样本类型 `S` 后追加，每帧只保留前 `keep` 个声道
+fn append<S: ConvertibleSample>(
+    sample_buf: &mut Option<SampleBuffer<S>>,
+    dest: &mut Vec<S>,
+    audio_buf: AudioBufferRef,
+    keep: usize,
+) {
+    let spec = *audio_buf.spec();
+    let channels = spec.channels.count();
+    let buf = sample_buf.get_or_insert_with(|| SampleBuffer::new(audio_buf.capacity() as u64, spec));
+    buf.copy_interleaved_ref(audio_buf);
+
+    if keep >= channels {
+        dest.extend_from_slice(buf.samples());
+    } else {
+        dest.reserve(buf.samples().len() / channels * keep);
+        for frame in buf.samples().chunks_exact(channels) {
+            dest.extend_from_slice(&frame[..keep]);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -205,6 +304,60 @@ mod tests {
         assert!(decoder.get_samples().len() > 0);
     }
 
+    #[test]
+    fn test_native_decode_is_bit_exact() {
+        let temp_file = create_test_wav_file(44100, 1, 0.05);
+        let expected: Vec<i16> = hound::WavReader::open(temp_file.path())
+            .unwrap()
+            .samples::<i16>()
+            .map(|s| s.unwrap())
+            .collect();
+
+        let mut decoder = AudioDecoder::new();
+        let options = DecodeOptions { native: true, max_channels: None, quiet: false };
+        decoder.decode_file_with(temp_file.path().to_str().unwrap(), &options).unwrap();
+        assert!(decoder.get_samples().is_empty());
+        assert_eq!(decoder.native_samples(), Some(&NativeSamples::I16(expected)));
+    }
+
+    #[test]
+    fn test_native_decode_keeps_32_bit_samples() {
+        // 低位不为零的 32 位样本无法用 f32 精确表示
+        let samples = [0x1234_5677, -0x0765_4321, i32::MAX, i32::MIN, 1];
+        let temp_file = NamedTempFile::new().unwrap();
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 8000,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(temp_file.path(), spec).unwrap();
+        for sample in samples {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let mut decoder = AudioDecoder::new();
+        let options = DecodeOptions { native: true, max_channels: None, quiet: false };
+        decoder.decode_file_with(temp_file.path().to_str().unwrap(), &options).unwrap();
+        assert_eq!(decoder.take_native_samples(), Some(NativeSamples::I32(samples.to_vec())));
+    }
+
+    #[test]
+    fn test_decode_drops_extra_channels() {
+        let temp_file = create_test_wav_file(44100, 2, 0.05);
+        let path = temp_file.path().to_str().unwrap();
+        let mut full = AudioDecoder::new();
+        full.decode_file(path).unwrap();
+
+        let mut decoder = AudioDecoder::new();
+        let options = DecodeOptions { native: false, max_channels: Some(1), quiet: false };
+        decoder.decode_file_with(path, &options).unwrap();
+        assert_eq!(decoder.get_channels(), 1);
+        let left: Vec<f32> = full.get_samples().iter().step_by(2).copied().collect();
+        assert_eq!(decoder.get_samples(), &left[..]);
+    }
+
     #[test]
     fn test_decode_different_sample_rates() {
         let sample_rates = vec![22050, 44100, 48000, 96000];
@@ -0,0 +1,386 @@
+//! 频谱特征提取：STFT 幅度谱、梅尔谱、对数梅尔谱和 MFCC
+//!
+//! 多声道输入先混合为单声道。帧不做居中补零，第 i 帧从第 `i * hop` 个样本开始，
+//! 不足一帧的尾部丢弃（整段音频短于一帧时补零得到一帧）。
+
+use crate::cli::args::{ArrayLayout, FeatureKind, OutputFormat, WindowType};
+use crate::config::settings::OutputSettings;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use anyhow::Result;
+
+/// 对数梅尔谱中能量的下限，避免 ln(0)
+const LOG_FLOOR: f64 = 1e-10;
+
+/// 特征提取配置
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct FeatureSettings {
+    pub kind: FeatureKind,
+    /// FFT 点数（同时也是窗长），必须为 2 的幂
+    #[serde(default = "default_fft_size")]
+    pub fft_size: usize,
+    /// 帧移（样本数）
+    #[serde(default = "default_hop")]
+    pub hop: usize,
+    #[serde(default)]
+    pub window: WindowType,
+    /// 梅尔滤波器个数
+    #[serde(default = "default_mel_bands")]
+    pub mel_bands: usize,
+    /// MFCC 系数个数
+    #[serde(default = "default_mfcc")]
+    pub mfcc: usize,
+}
+
+fn default_fft_size() -> usize {
+    512
+}
+
+fn default_hop() -> usize {
+    160
+}
+
+fn default_mel_bands() -> usize {
+    40
+}
+
+fn default_mfcc() -> usize {
+    13
+}
+
+impl FeatureSettings {
+    pub fn new(kind: FeatureKind) -> Self {
+        Self {
+            kind,
+            fft_size: default_fft_size(),
+            hop: default_hop(),
+            window: WindowType::default(),
+            mel_bands: default_mel_bands(),
+            mfcc: default_mfcc(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.fft_size < 2 || !self.fft_size.is_power_of_two() {
+            anyhow::bail!("FFT 点数必须是 2 的幂，当前为 {}", self.fft_size);
+        }
+        if self.hop == 0 {
+            anyhow::bail!("帧移必须大于 0");
+        }
+        if self.kind != FeatureKind::Stft && self.mel_bands == 0 {
+            anyhow::bail!("梅尔滤波器个数必须大于 0");
+        }
+        if self.kind == FeatureKind::Mfcc && (self.mfcc == 0 || self.mfcc > self.mel_bands) {
+            anyhow::bail!("MFCC 系数个数必须在 1 到 {} 之间，当前为 {}", self.mel_bands, self.mfcc);
+        }
+        Ok(())
+    }
+}
+
+/// 按行存储的特征矩阵，形状为 [帧数][特征维数]
+#[derive(Debug, Clone)]
+pub struct Features {
+    pub data: Vec<f32>,
+    pub frames: usize,
+    pub bins: usize,
+    pub sample_rate: u32,
+    pub settings: FeatureSettings,
+}
+
+impl Features {
+    pub fn row(&self, frame: usize) -> &[f32] {
+        &self.data[frame * self.bins..(frame + 1) * self.bins]
+    }
+
+    /// 写入输出文件的形状元数据
+    pub fn metadata(&self) -> serde_json::Value {
+        let mut metadata = serde_json::json!({
+            "kind": self.settings.kind.name(),
+            "shape": [self.frames, self.bins],
+            "sample_rate": self.sample_rate,
+            "fft_size": self.settings.fft_size,
+            "hop": self.settings.hop,
+            "window": self.settings.window.name(),
+        });
+        if self.settings.kind != FeatureKind::Stft {
+            metadata["mel_bands"] = self.settings.mel_bands.into();
+        }
+        metadata
+    }
+}
+
+/// 检查输出设置能否用于特征矩阵
+///
+/// 特征值不在 [-1, 1] 范围内，只能写为 f32/f64；矩阵固定为 [帧][维数] 排列，
+/// 不支持按声道拆分的布局和输出模板。
+pub fn check_output(format: OutputFormat, settings: &OutputSettings) -> Result<()> {
+    if format.bits().is_some() {
+        anyhow::bail!("特征矩阵只能输出为浮点格式 (f32/f64)，当前为 {}", format.name());
+    }
+    if let Some(template) = &settings.template {
+        anyhow::bail!("特征矩阵不支持输出模板: {}", template);
+    }
+    if matches!(settings.layout, ArrayLayout::Planar | ArrayLayout::PerChannel) {
+        anyhow::bail!("特征矩阵固定为 [帧][维数] 二维数组，不支持 {} 布局", settings.layout.name());
+    }
+    Ok(())
+}
+
+pub fn extract(samples: &[f32], sample_rate: u32, channels: u32, settings: &FeatureSettings) -> Result<Features> {
+    settings.validate()?;
+
+    let mono = downmix(samples, channels.max(1) as usize);
+    let spectrum = power_spectrogram(&mono, settings);
+    let spectrum_bins = settings.fft_size / 2 + 1;
+    let frames = spectrum.len() / spectrum_bins;
+
+    let (data, bins) = match settings.kind {
+        FeatureKind::Stft => (spectrum.iter().map(|p| p.sqrt()).collect::<Vec<_>>(), spectrum_bins),
+        kind => {
+            let filters = mel_filterbank(settings.mel_bands, settings.fft_size, sample_rate);
+            let mut mel = Vec::with_capacity(frames * settings.mel_bands);
+            for frame in spectrum.chunks_exact(spectrum_bins) {
+                for filter in &filters {
+                    mel.push(filter.iter().zip(frame).map(|(w, p)| w * p).sum::<f64>());
+                }
+            }
+
+            match kind {
+                FeatureKind::Mel => (mel, settings.mel_bands),
+                FeatureKind::LogMel => (log_compress(&mel), settings.mel_bands),
+                _ => {
+                    let log_mel = log_compress(&mel);
+                    let mfcc = log_mel
+                        .chunks_exact(settings.mel_bands)
+                        .flat_map(|frame| dct(frame, settings.mfcc))
+                        .collect();
+                    (mfcc, settings.mfcc)
+                }
+            }
+        }
+    };
+
+    Ok(Features {
+        data: data.into_iter().map(|v| v as f32).collect(),
+        frames,
+        bins,
+        sample_rate,
+        settings: settings.clone(),
+    })
+}
+
+fn downmix(samples: &[f32], channels: usize) -> Vec<f64> {
+    samples
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().map(|&s| s as f64).sum::<f64>() / channels as f64)
+        .collect()
+}
+
+/// 逐帧加窗后做 FFT，返回按行排列的功率谱 |X[k]|²，k = 0..=N/2
+fn power_spectrogram(samples: &[f64], settings: &FeatureSettings) -> Vec<f64> {
+    let n = settings.fft_size;
+    let window = window(settings.window, n);
+    let frames = if samples.len() <= n { 1 } else { 1 + (samples.len() - n) / settings.hop };
+
+    let mut spectrum = Vec::with_capacity(frames * (n / 2 + 1));
+    let mut re = vec![0.0; n];
+    let mut im = vec![0.0; n];
+    for frame in 0..frames {
+        let start = frame * settings.hop;
+        for i in 0..n {
+            re[i] = samples.get(start + i).copied().unwrap_or(0.0) * window[i];
+            im[i] = 0.0;
+        }
+        fft(&mut re, &mut im);
+        spectrum.extend((0..=n / 2).map(|k| re[k] * re[k] + im[k] * im[k]));
+    }
+
+    spectrum
+}
+
+/// 周期窗（适合 STFT 分析）
+fn window(window_type: WindowType, n: usize) -> Vec<f64> {
+    (0..n)
+        .map(|i| {
+            let phase = 2.0 * PI * i as f64 / n as f64;
+            match window_type {
+                WindowType::Hann => 0.5 - 0.5 * phase.cos(),
+                WindowType::Hamming => 0.54 - 0.46 * phase.cos(),
+                WindowType::Rect => 1.0,
+            }
+        })
+        .collect()
+}
+
+/// 原地基 2 FFT，长度必须为 2 的幂
+fn fft(re: &mut [f64], im: &mut [f64]) {
+    let n = re.len();
+    let bits = n.trailing_zeros();
+
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f64;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (w_im, w_re) = (angle * k as f64).sin_cos();
+                let a = start + k;
+                let b = a + len / 2;
+                let t_re = re[b] * w_re - im[b] * w_im;
+                let t_im = re[b] * w_im + im[b] * w_re;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len *= 2;
+    }
+}
+
+fn hz_to_mel(hz: f64) -> f64 {
+    2595.0 * (1.0 + hz / 700.0).log10()
+}
+
+fn mel_to_hz(mel: f64) -> f64 {
+    700.0 * (10f64.powf(mel / 2595.0) - 1.0)
+}
+
+/// 0 Hz 到奈奎斯特频率之间在梅尔刻度上等间隔的三角滤波器组（HTK 公式）
+fn mel_filterbank(bands: usize, fft_size: usize, sample_rate: u32) -> Vec<Vec<f64>> {
+    let nyquist = sample_rate as f64 / 2.0;
+    let max_mel = hz_to_mel(nyquist);
+    let edges: Vec<f64> = (0..bands + 2)
+        .map(|i| mel_to_hz(max_mel * i as f64 / (bands + 1) as f64))
+        .collect();
+
+    (0..bands)
+        .map(|band| {
+            let (left, center, right) = (edges[band], edges[band + 1], edges[band + 2]);
+            (0..=fft_size / 2)
+                .map(|k| {
+                    let freq = k as f64 * sample_rate as f64 / fft_size as f64;
+                    let rising = (freq - left) / (center - left);
+                    let falling = (right - freq) / (right - center);
+                    rising.min(falling).max(0.0)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn log_compress(values: &[f64]) -> Vec<f64> {
+    values.iter().map(|&v| v.max(LOG_FLOOR).ln()).collect()
+}
+
+/// 正交归一化的 DCT-II，只保留前 `count` 个系数
+fn dct(input: &[f64], count: usize) -> Vec<f64> {
+    let n = input.len() as f64;
+    (0..count)
+        .map(|k| {
+            let sum: f64 = input
+                .iter()
+                .enumerate()
+                .map(|(i, &x)| x * (PI * k as f64 * (i as f64 + 0.5) / n).cos())
+                .sum();
+            let scale = if k == 0 { (1.0 / n).sqrt() } else { (2.0 / n).sqrt() };
+            sum * scale
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq: f32, sample_rate: u32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2.0 * std::f32::consts::PI * freq * i as f32 / sample_rate as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_fft_matches_dft() {
+        let input: Vec<f64> = (0..16).map(|i| ((i * 7) % 5) as f64 - 2.0).collect();
+        let mut re = input.clone();
+        let mut im = vec![0.0; 16];
+        fft(&mut re, &mut im);
+
+        for k in 0..16 {
+            let (mut dft_re, mut dft_im) = (0.0, 0.0);
+            for (i, &x) in input.iter().enumerate() {
+                let angle = -2.0 * PI * (k * i) as f64 / 16.0;
+                dft_re += x * angle.cos();
+                dft_im += x * angle.sin();
+            }
+            assert!((re[k] - dft_re).abs() < 1e-9);
+            assert!((im[k] - dft_im).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_stft_peak_bin() {
+        // 1000 Hz 正弦，16 kHz 采样，512 点 FFT：峰值在第 32 个频点
+        let samples = sine(1000.0, 16000, 16000);
+        let features = extract(&samples, 16000, 1, &FeatureSettings::new(FeatureKind::Stft)).unwrap();
+
+        assert_eq!(features.bins, 257);
+        assert_eq!(features.frames, 1 + (16000 - 512) / 160);
+        let row = features.row(10);
+        let peak = (0..row.len()).max_by(|&a, &b| row[a].total_cmp(&row[b])).unwrap();
+        assert_eq!(peak, 32);
+    }
+
+    #[test]
+    fn test_mel_and_mfcc_shapes() {
+        let samples = sine(440.0, 16000, 4000);
+        let stereo: Vec<f32> = samples.iter().flat_map(|&s| [s, s]).collect();
+
+        let settings = FeatureSettings {
+            mel_bands: 20,
+            mfcc: 10,
+            ..FeatureSettings::new(FeatureKind::LogMel)
+        };
+        let log_mel = extract(&stereo, 16000, 2, &settings).unwrap();
+        assert_eq!(log_mel.bins, 20);
+        assert_eq!(log_mel.frames, 1 + (4000 - 512) / 160);
+
+        let mfcc = extract(&stereo, 16000, 2, &FeatureSettings { kind: FeatureKind::Mfcc, ..settings }).unwrap();
+        assert_eq!(mfcc.bins, 10);
+        assert_eq!(mfcc.frames, log_mel.frames);
+        assert!(mfcc.data.iter().all(|v| v.is_finite()));
+    }
+
+    #[test]
+    fn test_short_input_and_validation() {
+        let features = extract(&[0.5; 100], 8000, 1, &FeatureSettings::new(FeatureKind::Mel)).unwrap();
+        assert_eq!(features.frames, 1);
+
+        let settings = FeatureSettings {
+            fft_size: 500,
+            ..FeatureSettings::new(FeatureKind::Stft)
+        };
+        assert!(extract(&[0.0; 1000], 8000, 1, &settings).is_err());
+
+        let settings = FeatureSettings {
+            mfcc: 50,
+            ..FeatureSettings::new(FeatureKind::Mfcc)
+        };
+        assert!(extract(&[0.0; 1000], 8000, 1, &settings).is_err());
+    }
+
+    #[test]
+    fn test_dct_of_constant() {
+        let coefficients = dct(&[2.0; 8], 4);
+        assert!((coefficients[0] - 2.0 * 8f64.sqrt()).abs() < 1e-9);
+        assert!(coefficients[1..].iter().all(|c| c.abs() < 1e-9));
+    }
+}
@@ -0,0 +1,346 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use std::f64::consts::PI;
+use std::str::FromStr;
+use anyhow::Result;
+
+/// 直流阻断滤波器未指定截止频率时使用的默认值 (Hz)
+const DEFAULT_DC_BLOCK_CUTOFF: f32 = 10.0;
+
+/// 滤波器类型
+///
+/// 配置文件中的名称与命令行相同，按 [`FromStr`] 解析（如 `highpass`、`hp`、`HighPass`）。
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterType {
+    LowPass,
+    HighPass,
+    BandPass,
+    LowShelf,
+    HighShelf,
+    Notch,
+    /// 一阶直流阻断滤波器
+    DcBlock,
+}
+
+/// 单个滤波器的配置
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct FilterSettings {
+    pub filter_type: FilterType,
+    /// 截止/中心频率 (Hz)，直流阻断滤波器可省略
+    #[serde(default)]
+    pub cutoff: Option<f32>,
+    /// 品质因数
+    #[serde(default = "default_q")]
+    pub q: f32,
+    /// 搁架滤波器的增益 (dB)
+    #[serde(default)]
+    pub gain_db: f32,
+}
+
+fn default_q() -> f32 {
+    std::f32::consts::FRAC_1_SQRT_2
+}
+
+impl FromStr for FilterType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().replace(['-', '_'], "").as_str() {
+            "lowpass" | "lp" => Ok(FilterType::LowPass),
+            "highpass" | "hp" => Ok(FilterType::HighPass),
+            "bandpass" | "bp" => Ok(FilterType::BandPass),
+            "lowshelf" | "ls" => Ok(FilterType::LowShelf),
+            "highshelf" | "hs" => Ok(FilterType::HighShelf),
+            "notch" => Ok(FilterType::Notch),
+            "dcblock" | "dc" => Ok(FilterType::DcBlock),
+            _ => anyhow::bail!("未知的滤波器类型: {}", s),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for FilterType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// 解析命令行滤波器描述，格式为 `类型[:截止频率[:Q[:增益dB]]]`，例如 `highpass:80`
+impl FromStr for FilterSettings {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.split(':');
+        let filter_type: FilterType = parts.next().unwrap_or_default().parse()?;
+
+        let parse_number = |part: Option<&str>, name: &str| -> Result<Option<f32>> {
+            match part {
+                Some(value) if !value.is_empty() => value
+                    .parse::<f32>()
+                    .map(Some)
+                    .map_err(|_| anyhow::anyhow!("无效的滤波器{}: {}", name, value)),
+                _ => Ok(None),
+            }
+        };
+
+        let cutoff = parse_number(parts.next(), "截止频率")?;
+        let q = parse_number(parts.next(), "Q 值")?.unwrap_or_else(default_q);
+        let gain_db = parse_number(parts.next(), "增益")?.unwrap_or(0.0);
+
+        if parts.next().is_some() {
+            anyhow::bail!("滤波器参数过多: {}", s);
+        }
+
+        Ok(FilterSettings { filter_type, cutoff, q, gain_db })
+    }
+}
+
+/// 二阶 IIR 滤波器（直接 II 型转置结构）
+#[derive(Debug, Clone)]
+pub struct Biquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+    z1: f64,
+    z2: f64,
+}
+
+impl Biquad {
+    /// 由已归一化（a0 = 1）的系数创建滤波器
+    pub fn from_coefficients(b0: f64, b1: f64, b2: f64, a1: f64, a2: f64) -> Self {
+        Self { b0, b1, b2, a1, a2, z1: 0.0, z2: 0.0 }
+    }
+
+    /// 按 RBJ Audio EQ Cookbook 公式计算系数
+    pub fn new(settings: &FilterSettings, sample_rate: u32) -> Result<Self> {
+        let fs = sample_rate as f64;
+        let cutoff = match (settings.filter_type, settings.cutoff) {
+            (_, Some(cutoff)) => cutoff as f64,
+            (FilterType::DcBlock, None) => DEFAULT_DC_BLOCK_CUTOFF as f64,
+            (filter_type, None) => anyhow::bail!("{:?} 滤波器缺少截止频率", filter_type),
+        };
+
+        if sample_rate == 0 || cutoff <= 0.0 || cutoff >= fs / 2.0 {
+            anyhow::bail!(
+                "滤波器截止频率 {}Hz 超出范围 (0, {}Hz)",
+                cutoff,
+                fs / 2.0
+            );
+        }
+        if settings.q <= 0.0 {
+            anyhow::bail!("滤波器 Q 值必须大于 0: {}", settings.q);
+        }
+
+        if settings.filter_type == FilterType::DcBlock {
+            // y[n] = x[n] - x[n-1] + r * y[n-1]
+            let r = (-2.0 * PI * cutoff / fs).exp();
+            return Ok(Self::from_coefficients(1.0, -1.0, 0.0, -r, 0.0));
+        }
+
+        let w0 = 2.0 * PI * cutoff / fs;
+        let (sin_w0, cos_w0) = w0.sin_cos();
+        let alpha = sin_w0 / (2.0 * settings.q as f64);
+        let a = 10.0_f64.powf(settings.gain_db as f64 / 40.0);
+        let sqrt_a_alpha = 2.0 * a.sqrt() * alpha;
+
+        let (b0, b1, b2, a0, a1, a2) = match settings.filter_type {
+            FilterType::LowPass => (
+                (1.0 - cos_w0) / 2.0,
+                1.0 - cos_w0,
+                (1.0 - cos_w0) / 2.0,
+                1.0 + alpha,
+                -2.0 * cos_w0,
+                1.0 - alpha,
+            ),
+            FilterType::HighPass => (
+                (1.0 + cos_w0) / 2.0,
+                -(1.0 + cos_w0),
+                (1.0 + cos_w0) / 2.0,
+                1.0 + alpha,
+                -2.0 * cos_w0,
+                1.0 - alpha,
+            ),
+            FilterType::BandPass => (
+                alpha,
+                0.0,
+                -alpha,
+                1.0 + alpha,
+                -2.0 * cos_w0,
+                1.0 - alpha,
+            ),
+            FilterType::Notch => (
+                1.0,
+                -2.0 * cos_w0,
+                1.0,
+                1.0 + alpha,
+                -2.0 * cos_w0,
+                1.0 - alpha,
+            ),
+            FilterType::LowShelf => (
+                a * ((a + 1.0) - (a - 1.0) * cos_w0 + sqrt_a_alpha),
+                2.0 * a * ((a - 1.0) - (a + 1.0) * cos_w0),
+                a * ((a + 1.0) - (a - 1.0) * cos_w0 - sqrt_a_alpha),
+                (a + 1.0) + (a - 1.0) * cos_w0 + sqrt_a_alpha,
+                -2.0 * ((a - 1.0) + (a + 1.0) * cos_w0),
+                (a + 1.0) + (a - 1.0) * cos_w0 - sqrt_a_alpha,
+            ),
+            FilterType::HighShelf => (
+                a * ((a + 1.0) + (a - 1.0) * cos_w0 + sqrt_a_alpha),
+                -2.0 * a * ((a - 1.0) + (a + 1.0) * cos_w0),
+                a * ((a + 1.0) + (a - 1.0) * cos_w0 - sqrt_a_alpha),
+                (a + 1.0) - (a - 1.0) * cos_w0 + sqrt_a_alpha,
+                2.0 * ((a - 1.0) - (a + 1.0) * cos_w0),
+                (a + 1.0) - (a - 1.0) * cos_w0 - sqrt_a_alpha,
+            ),
+            FilterType::DcBlock => unreachable!(),
+        };
+
+        Ok(Self::from_coefficients(b0 / a0, b1 / a0, b2 / a0, a1 / a0, a2 / a0))
+    }
+
+    pub fn process(&mut self, input: f64) -> f64 {
+        let output = self.b0 * input + self.z1;
+        self.z1 = self.b1 * input - self.a1 * output + self.z2;
+        self.z2 = self.b2 * input - self.a2 * output;
+        output
+    }
+
+    pub fn reset(&mut self) {
+        self.z1 = 0.0;
+        self.z2 = 0.0;
+    }
+}
+
+/// 对交错排列的样本依次应用滤波器，每个声道维护独立的滤波器状态
+pub fn apply_filters(
+    samples: &mut [f32],
+    sample_rate: u32,
+    channels: u32,
+    filters: &[FilterSettings],
+) -> Result<()> {
+    let channel_count = channels.max(1) as usize;
+
+    for settings in filters {
+        let prototype = Biquad::new(settings, sample_rate)?;
+
+        // 各声道独立滤波，逐声道处理不需要分配状态数组
+        for channel in 0..channel_count {
+            let mut state = prototype.clone();
+            for sample in samples.iter_mut().skip(channel).step_by(channel_count) {
+                *sample = state.process(*sample as f64) as f32;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f32, sample_rate: u32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .map(|i| (i as f32 * frequency * 2.0 * std::f32::consts::PI / sample_rate as f32).sin())
+            .collect()
+    }
+
+    fn tail_peak(samples: &[f32]) -> f32 {
+        samples[samples.len() / 2..].iter().fold(0.0_f32, |m, s| m.max(s.abs()))
+    }
+
+    #[test]
+    fn test_parse_filter_settings() {
+        let settings: FilterSettings = "highpass:80".parse().unwrap();
+        assert_eq!(settings.filter_type, FilterType::HighPass);
+        assert_eq!(settings.cutoff, Some(80.0));
+        assert!((settings.q - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+
+        let settings: FilterSettings = "low-shelf:200:0.5:-6".parse().unwrap();
+        assert_eq!(settings.filter_type, FilterType::LowShelf);
+        assert_eq!(settings.q, 0.5);
+        assert_eq!(settings.gain_db, -6.0);
+
+        let settings: FilterSettings = "dcblock".parse().unwrap();
+        assert_eq!(settings.filter_type, FilterType::DcBlock);
+        assert_eq!(settings.cutoff, None);
+
+        assert!("unknown:100".parse::<FilterSettings>().is_err());
+        assert!("lowpass:abc".parse::<FilterSettings>().is_err());
+        assert!("lowpass:1:2:3:4".parse::<FilterSettings>().is_err());
+    }
+
+    #[test]
+    fn test_deserialize_filter_type_names() {
+        // 配置文件接受命令行的名称，也兼容原来的变体名
+        for (name, expected) in [
+            ("highpass", FilterType::HighPass),
+            ("hp", FilterType::HighPass),
+            ("HighPass", FilterType::HighPass),
+            ("dc-block", FilterType::DcBlock),
+            ("DcBlock", FilterType::DcBlock),
+            ("low_shelf", FilterType::LowShelf),
+        ] {
+            let parsed: FilterType = serde_json::from_value(serde_json::json!(name)).unwrap();
+            assert_eq!(parsed, expected);
+        }
+        assert!(serde_json::from_value::<FilterType>(serde_json::json!("bandstop")).is_err());
+
+        // 序列化结果可以再读回
+        let json = serde_json::to_value(FilterType::LowShelf).unwrap();
+        assert_eq!(serde_json::from_value::<FilterType>(json).unwrap(), FilterType::LowShelf);
+    }
+
+    #[test]
+    fn test_lowpass_attenuates_high_frequency() {
+        let settings: FilterSettings = "lowpass:1000".parse().unwrap();
+
+        let mut low = sine(100.0, 48000, 4800);
+        let mut high = sine(10000.0, 48000, 4800);
+        apply_filters(&mut low, 48000, 1, std::slice::from_ref(&settings)).unwrap();
+        apply_filters(&mut high, 48000, 1, &[settings]).unwrap();
+
+        assert!(tail_peak(&low) > 0.9);
+        assert!(tail_peak(&high) < 0.05);
+    }
+
+    #[test]
+    fn test_highpass_attenuates_low_frequency() {
+        let settings: FilterSettings = "highpass:1000".parse().unwrap();
+
+        let mut low = sine(50.0, 48000, 9600);
+        apply_filters(&mut low, 48000, 1, &[settings]).unwrap();
+
+        assert!(tail_peak(&low) < 0.01);
+    }
+
+    #[test]
+    fn test_dc_block_removes_offset() {
+        let mut samples: Vec<f32> = sine(440.0, 48000, 48000).iter().map(|s| s * 0.5 + 0.3).collect();
+        apply_filters(&mut samples, 48000, 1, &["dcblock".parse().unwrap()]).unwrap();
+
+        let tail = &samples[samples.len() / 2..];
+        let mean = tail.iter().sum::<f32>() / tail.len() as f32;
+        assert!(mean.abs() < 0.01, "mean = {}", mean);
+    }
+
+    #[test]
+    fn test_channels_have_independent_state() {
+        // 左声道为直流，右声道为静音；滤波后右声道必须保持静音
+        let mut samples: Vec<f32> = (0..4800).flat_map(|_| [1.0, 0.0]).collect();
+        apply_filters(&mut samples, 48000, 2, &["highpass:100".parse().unwrap()]).unwrap();
+
+        assert!(samples.iter().skip(1).step_by(2).all(|&s| s == 0.0));
+        assert!(samples[0] > 0.9);
+        assert!(samples[samples.len() - 2].abs() < 0.01);
+    }
+
+    #[test]
+    fn test_invalid_cutoff() {
+        let mut samples = vec![0.0; 16];
+        assert!(apply_filters(&mut samples, 8000, 1, &["lowpass:5000".parse().unwrap()]).is_err());
+        assert!(apply_filters(&mut samples, 8000, 1, &["lowpass".parse().unwrap()]).is_err());
+    }
+}
@@ -1,2 +1,9 @@
+pub mod analysis;
 pub mod converter;
 pub mod decoder;
+pub mod features;
+pub mod filter;
+pub mod peaks;
+pub mod pipeline;
+pub mod probe;
+pub mod simd;
@@ -0,0 +1,204 @@
+//! 波形峰值数据：按桶统计每个声道的最小值、最大值和可选的 RMS
+//!
+//! 与 audiowaveform 相同，每个桶覆盖 `samples_per_pixel` 帧，最后一个桶可以不满；
+//! 输出时按 `bits`（8 或 16）量化为有符号整数。
+
+use crate::output::sample_format;
+use serde_json::json;
+use anyhow::Result;
+
+/// 峰值计算配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeakSettings {
+    /// 每个桶的帧数
+    pub samples_per_pixel: Option<usize>,
+    /// 目标点数，按音频长度换算为桶大小；与 `samples_per_pixel` 二选一
+    pub points: Option<usize>,
+    /// 是否同时计算每个桶的 RMS
+    pub rms: bool,
+    /// 输出量化位数，8 或 16
+    pub bits: u32,
+}
+
+impl Default for PeakSettings {
+    fn default() -> Self {
+        Self {
+            samples_per_pixel: None,
+            points: None,
+            rms: false,
+            bits: 16,
+        }
+    }
+}
+
+/// 未指定桶大小和点数时使用的桶大小
+const DEFAULT_SAMPLES_PER_PIXEL: usize = 256;
+
+impl PeakSettings {
+    /// 根据帧数确定桶大小
+    pub fn bucket_size(&self, frames: usize) -> Result<usize> {
+        if self.bits != 8 && self.bits != 16 {
+            anyhow::bail!("峰值位数只能是 8 或 16，当前为 {}", self.bits);
+        }
+        match (self.samples_per_pixel, self.points) {
+            (Some(_), Some(_)) => anyhow::bail!("桶大小和目标点数不能同时指定"),
+            (Some(0), _) => anyhow::bail!("桶大小必须大于 0"),
+            (_, Some(0)) => anyhow::bail!("目标点数必须大于 0"),
+            (Some(size), None) => Ok(size),
+            (None, Some(points)) => Ok(frames.div_ceil(points).max(1)),
+            (None, None) => Ok(DEFAULT_SAMPLES_PER_PIXEL),
+        }
+    }
+}
+
+/// 峰值数据，`min`/`max`/`rms` 均按「点 × 声道」交错排列
+#[derive(Debug, Clone)]
+pub struct Peaks {
+    pub sample_rate: u32,
+    pub channels: u32,
+    pub samples_per_pixel: usize,
+    pub bits: u32,
+    /// 点数
+    pub length: usize,
+    pub min: Vec<f32>,
+    pub max: Vec<f32>,
+    pub rms: Option<Vec<f32>>,
+}
+
+impl Peaks {
+    /// audiowaveform 格式的头部字段
+    pub fn metadata(&self) -> serde_json::Value {
+        json!({
+            "version": 2,
+            "channels": self.channels,
+            "sample_rate": self.sample_rate,
+            "samples_per_pixel": self.samples_per_pixel,
+            "bits": self.bits,
+            "length": self.length
+        })
+    }
+
+    /// 量化后的峰值，每个点依次为各声道的 min、max
+    pub fn data(&self) -> Vec<i32> {
+        self.min
+            .iter()
+            .zip(&self.max)
+            .flat_map(|(&low, &high)| [self.quantize(low), self.quantize(high)])
+            .collect()
+    }
+
+    /// 量化后的 RMS，每个点依次为各声道的值
+    pub fn rms_data(&self) -> Option<Vec<i32>> {
+        self.rms
+            .as_ref()
+            .map(|rms| rms.iter().map(|&v| self.quantize(v)).collect())
+    }
+
+    fn quantize(&self, value: f32) -> i32 {
+        sample_format::quantize(value, self.bits)
+    }
+}
+
+/// 计算交错排列样本的峰值数据
+pub fn compute(samples: &[f32], sample_rate: u32, channels: u32, settings: &PeakSettings) -> Result<Peaks> {
+    let channel_count = channels.max(1) as usize;
+    let frames = samples.len() / channel_count;
+    let samples_per_pixel = settings.bucket_size(frames)?;
+    let length = frames.div_ceil(samples_per_pixel);
+
+    let mut min = Vec::with_capacity(length * channel_count);
+    let mut max = Vec::with_capacity(length * channel_count);
+    let mut rms = settings.rms.then(|| Vec::with_capacity(length * channel_count));
+
+    for bucket in samples[..frames * channel_count].chunks(samples_per_pixel * channel_count) {
+        let bucket_frames = bucket.len() / channel_count;
+        for ch in 0..channel_count {
+            let mut low = f32::INFINITY;
+            let mut high = f32::NEG_INFINITY;
+            let mut sum_squares = 0.0f64;
+            for &sample in bucket.iter().skip(ch).step_by(channel_count) {
+                low = low.min(sample);
+                high = high.max(sample);
+                sum_squares += sample as f64 * sample as f64;
+            }
+            min.push(low);
+            max.push(high);
+            if let Some(rms) = rms.as_mut() {
+                rms.push((sum_squares / bucket_frames as f64).sqrt() as f32);
+            }
+        }
+    }
+
+    Ok(Peaks {
+        sample_rate,
+        channels: channel_count as u32,
+        samples_per_pixel,
+        bits: settings.bits,
+        length,
+        min,
+        max,
+        rms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(samples_per_pixel: Option<usize>, points: Option<usize>) -> PeakSettings {
+        PeakSettings {
+            samples_per_pixel,
+            points,
+            rms: true,
+            bits: 8,
+        }
+    }
+
+    #[test]
+    fn test_buckets() {
+        // 立体声，5 帧，每桶 2 帧 → 3 个点，最后一个点只有 1 帧
+        let samples = [0.1, -0.5, -0.2, 0.5, 0.3, 0.0, 0.4, -1.0, -0.6, 0.25];
+        let peaks = compute(&samples, 8000, 2, &settings(Some(2), None)).unwrap();
+
+        assert_eq!(peaks.length, 3);
+        assert_eq!(peaks.min, vec![-0.2, -0.5, 0.3, -1.0, -0.6, 0.25]);
+        assert_eq!(peaks.max, vec![0.1, 0.5, 0.4, 0.0, -0.6, 0.25]);
+
+        let rms = peaks.rms.unwrap();
+        assert!((rms[1] - 0.5).abs() < 1e-6);
+        assert!((rms[4] - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_target_points() {
+        let samples = vec![0.0; 1000];
+        let peaks = compute(&samples, 8000, 1, &settings(None, Some(300))).unwrap();
+        assert_eq!(peaks.samples_per_pixel, 4);
+        assert_eq!(peaks.length, 250);
+
+        let peaks = compute(&samples, 8000, 1, &PeakSettings::default()).unwrap();
+        assert_eq!(peaks.samples_per_pixel, DEFAULT_SAMPLES_PER_PIXEL);
+        assert_eq!(peaks.length, 4);
+        assert!(peaks.rms.is_none());
+    }
+
+    #[test]
+    fn test_invalid_settings() {
+        assert!(compute(&[0.0], 8000, 1, &settings(Some(0), None)).is_err());
+        assert!(compute(&[0.0], 8000, 1, &settings(None, Some(0))).is_err());
+        assert!(compute(&[0.0], 8000, 1, &settings(Some(4), Some(4))).is_err());
+
+        let mut bad_bits = settings(Some(4), None);
+        bad_bits.bits = 12;
+        assert!(compute(&[0.0], 8000, 1, &bad_bits).is_err());
+    }
+
+    #[test]
+    fn test_quantized_data() {
+        let samples = [0.5, -1.0, 1.0, 0.0];
+        let peaks = compute(&samples, 8000, 1, &settings(Some(2), None)).unwrap();
+        assert_eq!(peaks.data(), vec![-128, 64, 0, 127]);
+        assert_eq!(peaks.metadata()["bits"], 8);
+        assert_eq!(peaks.metadata()["length"], 2);
+    }
+}
@@ -0,0 +1,405 @@
+use crate::audio::{analysis, filter, simd};
+use crate::audio::filter::FilterSettings;
+use crate::config::settings::Config;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use anyhow::Result;
+
+/// 无状态的逐样本处理按该大小分块并行，块内顺序处理，结果与顺序执行完全一致
+pub const PARALLEL_CHUNK: usize = 16 * 1024;
+
+/// 在处理链中流转的音频数据（交错排列）
+#[derive(Debug, Clone)]
+pub struct AudioBuffer {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    pub channels: u32,
+}
+
+/// 处理链中的一个步骤
+///
+/// 内置的增益、滤波、重采样、声道转换和归一化都实现了该 trait，
+/// 库使用者也可以实现自己的步骤并插入到 `AudioConverter` 中。
+pub trait ProcessingStage: Send + Sync {
+    /// 步骤名称，用于日志输出
+    fn name(&self) -> &str;
+
+    /// 处理缓冲区，可以修改样本、采样率和声道数
+    fn process(&self, buffer: &mut AudioBuffer) -> Result<()>;
+
+    /// 使用可复用的临时缓冲区处理
+    ///
+    /// 改变样本数的步骤应把结果写入 `scratch` 再与 `buffer.samples` 交换，使重复转换时复用两者的容量。
+    /// 默认直接调用 `process`。
+    fn process_with(&self, buffer: &mut AudioBuffer, scratch: &mut Vec<f32>) -> Result<()> {
+        let _ = scratch;
+        self.process(buffer)
+    }
+
+    /// 对指定采样率和声道数的输入是否不做任何改动，默认为 `false`
+    ///
+    /// 整条处理链都不做改动时，整数 PCM 输入可以跳过浮点处理直接输出。
+    fn is_identity(&self, sample_rate: u32, channels: u32) -> bool {
+        let _ = (sample_rate, channels);
+        false
+    }
+
+    /// 步骤如何使用输入声道，默认为 `ChannelUsage::All`
+    fn channel_usage(&self) -> ChannelUsage {
+        ChannelUsage::All
+    }
+}
+
+/// 处理步骤对输入声道的使用方式，决定多余的声道能否在解码时丢弃
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelUsage {
+    /// 各声道独立处理，丢弃部分声道不影响其余声道的结果
+    Independent,
+    /// 只使用前 N 个声道
+    First(u32),
+    /// 结果依赖全部声道
+    All,
+}
+
+/// 配置文件中的处理步骤，省略的参数取自 `Config` 中对应的顶层字段
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum ProcessingStep {
+    Gain {
+        #[serde(default)]
+        db: Option<f32>,
+    },
+    Filter {
+        #[serde(default)]
+        filters: Option<Vec<FilterSettings>>,
+    },
+    Resample {
+        #[serde(default)]
+        sample_rate: Option<u32>,
+    },
+    Channels {
+        #[serde(default)]
+        channels: Option<u32>,
+    },
+    Normalize,
+}
+
+/// 未配置处理链时使用的默认顺序
+pub fn default_chain() -> Vec<ProcessingStep> {
+    vec![
+        ProcessingStep::Gain { db: None },
+        ProcessingStep::Filter { filters: None },
+        ProcessingStep::Resample { sample_rate: None },
+        ProcessingStep::Channels { channels: None },
+        ProcessingStep::Normalize,
+    ]
+}
+
+/// 解析命令行的处理链描述，例如 `gain,resample,normalize`，
+/// 步骤参数取自顶层配置；`default` 返回 `None`，即使用默认顺序
+pub fn parse_chain(text: &str) -> Result<Option<Vec<ProcessingStep>>> {
+    if text.trim().eq_ignore_ascii_case("default") {
+        return Ok(None);
+    }
+    let steps = text
+        .split(',')
+        .map(|name| match name.trim().to_lowercase().as_str() {
+            "gain" => Ok(ProcessingStep::Gain { db: None }),
+            "filter" => Ok(ProcessingStep::Filter { filters: None }),
+            "resample" => Ok(ProcessingStep::Resample { sample_rate: None }),
+            "channels" => Ok(ProcessingStep::Channels { channels: None }),
+            "normalize" => Ok(ProcessingStep::Normalize),
+            other => anyhow::bail!("未知的处理步骤: {}（可用: gain, filter, resample, channels, normalize）", other),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Some(steps))
+}
+
+/// 根据配置构建处理链，参数最终为空操作的步骤会被跳过
+pub fn build_stages(config: &Config) -> Vec<Box<dyn ProcessingStage>> {
+    let explicit_chain = config.processing_chain.is_some();
+    let chain = config.processing_chain.clone().unwrap_or_else(default_chain);
+
+    let mut stages: Vec<Box<dyn ProcessingStage>> = Vec::new();
+    for step in chain {
+        match step {
+            ProcessingStep::Gain { db } => {
+                let gain_db = db.unwrap_or(config.gain);
+                if gain_db != 0.0 {
+                    stages.push(Box::new(GainStage { gain_db }));
+                }
+            }
+            ProcessingStep::Filter { filters } => {
+                let filters = filters.unwrap_or_else(|| config.filters.clone());
+                if !filters.is_empty() {
+                    stages.push(Box::new(FilterStage { filters }));
+                }
+            }
+            ProcessingStep::Resample { sample_rate } => {
+                if let Some(target_rate) = sample_rate.or(config.sample_rate) {
+                    stages.push(Box::new(ResampleStage { target_rate }));
+                }
+            }
+            ProcessingStep::Channels { channels } => {
+                if let Some(target_channels) = channels.or(config.channels) {
+                    stages.push(Box::new(ChannelStage { target_channels }));
+                }
+            }
+            ProcessingStep::Normalize => {
+                // 显式列出的归一化步骤总是执行
+                if explicit_chain || config.normalize {
+                    stages.push(Box::new(NormalizeStage));
+                }
+            }
+        }
+    }
+
+    stages
+}
+
+/// 音量增益 (dB)
+pub struct GainStage {
+    pub gain_db: f32,
+}
+
+impl ProcessingStage for GainStage {
+    fn name(&self) -> &str {
+        "gain"
+    }
+
+    fn channel_usage(&self) -> ChannelUsage {
+        ChannelUsage::Independent
+    }
+
+    fn process(&self, buffer: &mut AudioBuffer) -> Result<()> {
+        let gain_factor = 10.0_f32.powf(self.gain_db / 20.0);
+        scale(&mut buffer.samples, gain_factor);
+        Ok(())
+    }
+}
+
+/// 按顺序应用一组滤波器
+pub struct FilterStage {
+    pub filters: Vec<FilterSettings>,
+}
+
+impl ProcessingStage for FilterStage {
+    fn name(&self) -> &str {
+        "filter"
+    }
+
+    fn channel_usage(&self) -> ChannelUsage {
+        ChannelUsage::Independent
+    }
+
+    fn process(&self, buffer: &mut AudioBuffer) -> Result<()> {
+        filter::apply_filters(&mut buffer.samples, buffer.sample_rate, buffer.channels, &self.filters)
+    }
+}
+
+/// 线性插值重采样（简化实现）
+pub struct ResampleStage {
+    pub target_rate: u32,
+}
+
+impl ProcessingStage for ResampleStage {
+    fn name(&self) -> &str {
+        "resample"
+    }
+
+    fn is_identity(&self, sample_rate: u32, _channels: u32) -> bool {
+        self.target_rate == sample_rate
+    }
+
+    fn process(&self, buffer: &mut AudioBuffer) -> Result<()> {
+        self.process_with(buffer, &mut Vec::new())
+    }
+
+    fn process_with(&self, buffer: &mut AudioBuffer, scratch: &mut Vec<f32>) -> Result<()> {
+        if self.target_rate == buffer.sample_rate {
+            return Ok(());
+        }
+
+        let samples = &buffer.samples;
+        let ratio = self.target_rate as f64 / buffer.sample_rate as f64;
+        let new_length = (samples.len() as f64 * ratio) as usize;
+        let resampled = scratch;
+        resampled.clear();
+        resampled.reserve(new_length);
+
+        for i in 0..new_length {
+            let pos = i as f64 / ratio;
+            let index = pos as usize;
+
+            if index + 1 < samples.len() {
+                let frac = pos - index as f64;
+                let sample = samples[index] * (1.0 - frac as f32) + samples[index + 1] * frac as f32;
+                resampled.push(sample);
+            } else if index < samples.len() {
+                resampled.push(samples[index]);
+            }
+        }
+
+        std::mem::swap(&mut buffer.samples, resampled);
+        buffer.sample_rate = self.target_rate;
+        Ok(())
+    }
+}
+
+/// 单声道/立体声互转
+pub struct ChannelStage {
+    pub target_channels: u32,
+}
+
+impl ProcessingStage for ChannelStage {
+    fn name(&self) -> &str {
+        "channels"
+    }
+
+    fn is_identity(&self, _sample_rate: u32, channels: u32) -> bool {
+        self.target_channels == channels
+    }
+
+    /// 多于两个声道的源只使用前 max(目标声道数, 2) 个声道，其余声道不会被读取
+    fn channel_usage(&self) -> ChannelUsage {
+        ChannelUsage::First(self.target_channels.max(2))
+    }
+
+    fn process(&self, buffer: &mut AudioBuffer) -> Result<()> {
+        self.process_with(buffer, &mut Vec::new())
+    }
+
+    fn process_with(&self, buffer: &mut AudioBuffer, scratch: &mut Vec<f32>) -> Result<()> {
+        let input = &buffer.samples;
+        match (buffer.channels, self.target_channels) {
+            (from, to) if from > 2 && to < from => {
+                // 先只保留前 max(to, 2) 个声道，与解码时丢弃多余声道的结果一致，再按立体声处理
+                let (from, keep) = (from as usize, to.max(2) as usize);
+                scratch.clear();
+                scratch.extend(input.chunks_exact(from).flat_map(|frame| &frame[..keep]));
+                std::mem::swap(&mut buffer.samples, scratch);
+                buffer.channels = keep as u32;
+                return self.process_with(buffer, scratch);
+            }
+            (2, 1) => {
+                // 立体声转单声道
+                scratch.clear();
+                scratch.resize(input.len() / 2, 0.0);
+                let input = &input[..scratch.len() * 2];
+                if input.len() <= PARALLEL_CHUNK * 2 {
+                    simd::stereo_to_mono(input, scratch);
+                } else {
+                    scratch
+                        .par_chunks_mut(PARALLEL_CHUNK)
+                        .zip(input.par_chunks(PARALLEL_CHUNK * 2))
+                        .for_each(|(output, input)| simd::stereo_to_mono(input, output));
+                }
+                std::mem::swap(&mut buffer.samples, scratch);
+            }
+            (1, 2) => {
+                // 单声道转立体声
+                scratch.clear();
+                scratch.resize(input.len() * 2, 0.0);
+                if input.len() <= PARALLEL_CHUNK {
+                    simd::mono_to_stereo(input, scratch);
+                } else {
+                    scratch
+                        .par_chunks_mut(PARALLEL_CHUNK * 2)
+                        .zip(input.par_chunks(PARALLEL_CHUNK))
+                        .for_each(|(output, input)| simd::mono_to_stereo(input, output));
+                }
+                std::mem::swap(&mut buffer.samples, scratch);
+            }
+            _ => {}
+        }
+
+        buffer.channels = self.target_channels;
+        Ok(())
+    }
+}
+
+/// 峰值归一化到 1.0
+pub struct NormalizeStage;
+
+impl ProcessingStage for NormalizeStage {
+    fn name(&self) -> &str {
+        "normalize"
+    }
+
+    fn process(&self, buffer: &mut AudioBuffer) -> Result<()> {
+        let max_val = analysis::peak(&buffer.samples);
+        if max_val > 0.0 {
+            scale(&mut buffer.samples, 1.0 / max_val);
+        }
+        Ok(())
+    }
+}
+
+/// 所有样本乘以同一系数（超过一个分块时并行，块内使用向量化内核）
+fn scale(samples: &mut [f32], factor: f32) {
+    if samples.len() <= PARALLEL_CHUNK {
+        simd::scale(samples, factor);
+    } else {
+        samples
+            .par_chunks_mut(PARALLEL_CHUNK)
+            .for_each(|chunk| simd::scale(chunk, factor));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stage_names(config: &Config) -> Vec<String> {
+        build_stages(config).iter().map(|s| s.name().to_string()).collect()
+    }
+
+    #[test]
+    fn test_default_chain_skips_noop_steps() {
+        let config = Config::default();
+        assert!(stage_names(&config).is_empty());
+
+        let config = Config {
+            gain: 3.0,
+            channels: Some(1),
+            normalize: true,
+            ..Default::default()
+        };
+        assert_eq!(stage_names(&config), vec!["gain", "channels", "normalize"]);
+    }
+
+    #[test]
+    fn test_explicit_chain_order_and_fallback() {
+        let config = Config {
+            gain: 6.0,
+            sample_rate: Some(16000),
+            processing_chain: Some(vec![
+                ProcessingStep::Channels { channels: Some(1) },
+                ProcessingStep::Resample { sample_rate: None },
+                ProcessingStep::Normalize,
+                ProcessingStep::Gain { db: Some(-3.0) },
+            ]),
+            ..Default::default()
+        };
+
+        assert_eq!(stage_names(&config), vec!["channels", "resample", "normalize", "gain"]);
+    }
+
+    #[test]
+    fn test_parse_chain_from_json() {
+        let chain: Vec<ProcessingStep> = serde_json::from_str(
+            r#"[
+                {"type": "Channels", "channels": 1},
+                {"type": "Resample"},
+                {"type": "Filter", "filters": [{"filter_type": "HighPass", "cutoff": 80.0}]},
+                {"type": "Normalize"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(chain.len(), 4);
+        assert_eq!(chain[0], ProcessingStep::Channels { channels: Some(1) });
+        assert_eq!(chain[1], ProcessingStep::Resample { sample_rate: None });
+        assert_eq!(chain[3], ProcessingStep::Normalize);
+    }
+}
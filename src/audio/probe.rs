@@ -0,0 +1,320 @@
+use serde::Serialize;
+use symphonia::core::codecs::CODEC_TYPE_NULL;
+use symphonia::core::formats::{FormatOptions, Track};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, MetadataRevision};
+use symphonia::core::probe::{Hint, ProbeResult};
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use anyhow::Result;
+
+/// 音频文件信息（仅通过探测文件头获得，不解码音频数据）
+#[derive(Serialize, Debug, Clone)]
+pub struct AudioInfo {
+    pub path: String,
+    pub container: String,
+    pub file_size: u64,
+    /// 默认音频轨道的 ID（解码时使用的轨道）
+    pub default_track: Option<u32>,
+    pub tracks: Vec<TrackInfo>,
+    pub tags: Vec<TagInfo>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TrackInfo {
+    pub id: u32,
+    pub codec: String,
+    pub codec_long_name: String,
+    pub sample_format: Option<String>,
+    pub bits_per_sample: Option<u32>,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u32>,
+    pub channel_layout: Option<String>,
+    pub frames: Option<u64>,
+    pub duration_seconds: Option<f64>,
+    pub language: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TagInfo {
+    pub key: String,
+    pub value: String,
+}
+
+/// 打开音频文件并探测其容器格式，返回可供读取数据包的格式读取器
+pub fn open_format(path: &str) -> Result<ProbeResult> {
+    let file = File::open(path)?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    // 创建格式提示
+    let mut hint = Hint::new();
+    if let Some(extension) = std::path::Path::new(path).extension() {
+        if let Some(ext_str) = extension.to_str() {
+            hint.with_extension(ext_str);
+        }
+    }
+
+    let meta_opts: MetadataOptions = Default::default();
+    let fmt_opts: FormatOptions = Default::default();
+
+    let probed = symphonia::default::get_probe()
+        .format(&hint, mss, &fmt_opts, &meta_opts)?;
+
+    Ok(probed)
+}
+
+/// 探测音频文件信息，只读取文件头和元数据
+pub fn probe_file(path: &str) -> Result<AudioInfo> {
+    let file_size = std::fs::metadata(path)?.len();
+    let container = detect_container(path)?;
+    let mut probed = open_format(path)?;
+
+    let tracks: Vec<TrackInfo> = probed.format.tracks().iter().map(track_info).collect();
+    let default_track = probed
+        .format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .map(|t| t.id);
+
+    // 容器外的元数据（如 MP3 的 ID3 标签）与容器内的元数据都需要收集
+    let mut tags = Vec::new();
+    if let Some(mut metadata) = probed.metadata.get() {
+        if let Some(revision) = metadata.skip_to_latest() {
+            tags.extend(collect_tags(revision));
+        }
+    }
+    if let Some(revision) = probed.format.metadata().skip_to_latest() {
+        tags.extend(collect_tags(revision));
+    }
+
+    Ok(AudioInfo {
+        path: path.to_string(),
+        container,
+        file_size,
+        default_track,
+        tracks,
+        tags,
+    })
+}
+
+fn track_info(track: &Track) -> TrackInfo {
+    let params = &track.codec_params;
+    let descriptor = symphonia::default::get_codecs().get_codec(params.codec);
+
+    let channels = params.channels.map(|c| c.count() as u32);
+    let channel_layout = match (params.channel_layout, params.channels) {
+        (Some(layout), _) => Some(format!("{:?}", layout)),
+        (None, Some(ch)) => Some(
+            ch.iter()
+                .map(|c| format!("{:?}", c))
+                .collect::<Vec<_>>()
+                .join(" | "),
+        ),
+        (None, None) => None,
+    };
+
+    let duration_seconds = match (params.time_base, params.n_frames) {
+        (Some(tb), Some(frames)) => {
+            let time = tb.calc_time(frames);
+            Some(time.seconds as f64 + time.frac)
+        }
+        (None, Some(frames)) => params.sample_rate.map(|sr| frames as f64 / sr as f64),
+        _ => None,
+    };
+
+    TrackInfo {
+        id: track.id,
+        codec: descriptor
+            .map(|d| d.short_name.to_string())
+            .unwrap_or_else(|| format!("{}", params.codec)),
+        codec_long_name: descriptor
+            .map(|d| d.long_name.to_string())
+            .unwrap_or_else(|| "未知编解码器".to_string()),
+        sample_format: params
+            .sample_format
+            .map(|f| format!("{:?}", f).to_lowercase())
+            .or_else(|| descriptor.and_then(|d| pcm_sample_format(d.short_name))),
+        bits_per_sample: params.bits_per_sample.or(params.bits_per_coded_sample),
+        sample_rate: params.sample_rate,
+        channels,
+        channel_layout,
+        frames: params.n_frames,
+        duration_seconds,
+        language: track.language.clone(),
+    }
+}
+
+/// PCM 轨道通常不填写 sample_format，从编解码器名称（如 pcm_s16le）推断
+fn pcm_sample_format(codec_name: &str) -> Option<String> {
+    let format = codec_name.strip_prefix("pcm_")?;
+    let format = format
+        .strip_suffix("le")
+        .or_else(|| format.strip_suffix("be"))
+        .unwrap_or(format);
+    let format = format.strip_suffix("_planar").unwrap_or(format);
+    Some(format.to_string())
+}
+
+fn collect_tags(revision: &MetadataRevision) -> Vec<TagInfo> {
+    revision
+        .tags()
+        .iter()
+        .map(|tag| TagInfo {
+            key: match tag.std_key {
+                Some(std_key) => format!("{:?}", std_key),
+                None => tag.key.clone(),
+            },
+            value: tag.value.to_string().trim_end_matches('\0').to_string(),
+        })
+        .collect()
+}
+
+/// 根据文件头魔数识别容器格式，无法识别时退回到扩展名
+fn detect_container(path: &str) -> Result<String> {
+    let mut header = [0u8; 12];
+    let mut file = File::open(path)?;
+    let read = file.read(&mut header)?;
+    let header = &header[..read];
+
+    let container = if header.len() >= 12 && &header[0..4] == b"RIFF" && &header[8..12] == b"WAVE" {
+        "WAV"
+    } else if header.starts_with(b"fLaC") {
+        "FLAC"
+    } else if header.starts_with(b"OggS") {
+        "OGG"
+    } else if header.len() >= 8 && &header[4..8] == b"ftyp" {
+        "MP4"
+    } else if header.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+        "MKV/WebM"
+    } else if header.starts_with(b"caff") {
+        "CAF"
+    } else if header.starts_with(b"ID3") {
+        "MP3"
+    } else if header.len() >= 2 && header[0] == 0xFF && (header[1] & 0xF6) == 0xF0 {
+        "AAC (ADTS)"
+    } else if header.len() >= 2 && header[0] == 0xFF && (header[1] & 0xE0) == 0xE0 {
+        "MP3"
+    } else {
+        return Ok(std::path::Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_uppercase())
+            .unwrap_or_else(|| "未知".to_string()));
+    };
+
+    Ok(container.to_string())
+}
+
+impl fmt::Display for AudioInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "文件: {}", self.path)?;
+        writeln!(f, "容器格式: {}", self.container)?;
+        writeln!(f, "文件大小: {} 字节", self.file_size)?;
+        writeln!(f, "轨道数: {}", self.tracks.len())?;
+
+        for track in &self.tracks {
+            let marker = if Some(track.id) == self.default_track { " (默认)" } else { "" };
+            writeln!(f, "轨道 #{}{}:", track.id, marker)?;
+            writeln!(f, "  编解码器: {} ({})", track.codec, track.codec_long_name)?;
+            if let Some(sample_format) = &track.sample_format {
+                writeln!(f, "  采样格式: {}", sample_format)?;
+            }
+            if let Some(bits) = track.bits_per_sample {
+                writeln!(f, "  位深: {} bit", bits)?;
+            }
+            if let Some(sr) = track.sample_rate {
+                writeln!(f, "  采样率: {}Hz", sr)?;
+            }
+            if let Some(ch) = track.channels {
+                writeln!(f, "  声道数: {}", ch)?;
+            }
+            if let Some(layout) = &track.channel_layout {
+                writeln!(f, "  声道布局: {}", layout)?;
+            }
+            if let Some(frames) = track.frames {
+                writeln!(f, "  帧数: {}", frames)?;
+            }
+            if let Some(duration) = track.duration_seconds {
+                writeln!(f, "  时长: {:.3} 秒", duration)?;
+            }
+            if let Some(language) = &track.language {
+                writeln!(f, "  语言: {}", language)?;
+            }
+        }
+
+        if !self.tags.is_empty() {
+            writeln!(f, "标签:")?;
+            for tag in &self.tags {
+                writeln!(f, "  {}: {}", tag.key, tag.value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+    use std::io::Write;
+
+    fn create_test_wav_file(sample_rate: u32, channels: u16, frames: usize) -> NamedTempFile {
+        let temp_file = NamedTempFile::new().unwrap();
+        let spec = hound::WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+
+        let mut writer = hound::WavWriter::create(temp_file.path(), spec).unwrap();
+        for i in 0..frames {
+            for _ in 0..channels {
+                writer.write_sample((i % 100) as i16).unwrap();
+            }
+        }
+
+        writer.finalize().unwrap();
+        temp_file
+    }
+
+    #[test]
+    fn test_probe_wav_file() {
+        let temp_file = create_test_wav_file(22050, 2, 2205);
+        let info = probe_file(temp_file.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(info.container, "WAV");
+        assert_eq!(info.tracks.len(), 1);
+        assert_eq!(info.default_track, Some(info.tracks[0].id));
+
+        let track = &info.tracks[0];
+        assert_eq!(track.sample_rate, Some(22050));
+        assert_eq!(track.channels, Some(2));
+        assert_eq!(track.sample_format.as_deref(), Some("s16"));
+        assert_eq!(track.bits_per_sample, Some(16));
+        assert_eq!(track.frames, Some(2205));
+        assert!((track.duration_seconds.unwrap() - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_probe_invalid_file() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"This is not an audio file").unwrap();
+
+        assert!(probe_file(temp_file.path().to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_audio_info_display() {
+        let temp_file = create_test_wav_file(8000, 1, 800);
+        let info = probe_file(temp_file.path().to_str().unwrap()).unwrap();
+        let text = info.to_string();
+
+        assert!(text.contains("容器格式: WAV"));
+        assert!(text.contains("采样率: 8000Hz"));
+        assert!(text.contains("声道数: 1"));
+    }
+}
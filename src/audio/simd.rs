@@ -0,0 +1,251 @@
+//! 逐样本处理的向量化内核
+//!
+//! x86_64 上运行时检测 AVX2，可用时每次处理 8 个 f32（量化为 4 个 f64），其余平台和尾部样本使用标量实现
+//! （编译器会将其自动向量化为基线 SSE2）。所有内核与标量实现的结果逐位相同：
+//! 乘法、取最大值和除以 2 在 IEEE 754 下与执行顺序无关，量化按 `sample_format::quantize` 的规则
+//! 四舍五入（远离零）并饱和，NaN 量化为 0。
+
+#[cfg(target_arch = "x86_64")]
+fn has_avx2() -> bool {
+    std::arch::is_x86_feature_detected!("avx2")
+}
+
+/// 所有样本乘以同一系数
+pub fn scale(samples: &mut [f32], factor: f32) {
+    #[cfg(target_arch = "x86_64")]
+    if has_avx2() {
+        // SAFETY: 已在运行时确认 CPU 支持 AVX2
+        return unsafe { avx2::scale(samples, factor) };
+    }
+    scalar::scale(samples, factor)
+}
+
+/// 绝对值的最大值（忽略 NaN），空输入为 0
+pub fn peak(samples: &[f32]) -> f32 {
+    #[cfg(target_arch = "x86_64")]
+    if has_avx2() {
+        // SAFETY: 同上
+        return unsafe { avx2::peak(samples) };
+    }
+    scalar::peak(samples)
+}
+
+/// 交错立体声混合为单声道，`output` 长度为输入帧数
+pub fn stereo_to_mono(input: &[f32], output: &mut [f32]) {
+    assert_eq!(input.len() / 2, output.len());
+    #[cfg(target_arch = "x86_64")]
+    if has_avx2() {
+        // SAFETY: 同上
+        return unsafe { avx2::stereo_to_mono(input, output) };
+    }
+    scalar::stereo_to_mono(input, output)
+}
+
+/// 单声道复制为交错立体声，`output` 长度为输入的 2 倍
+pub fn mono_to_stereo(input: &[f32], output: &mut [f32]) {
+    assert_eq!(input.len() * 2, output.len());
+    #[cfg(target_arch = "x86_64")]
+    if has_avx2() {
+        // SAFETY: 同上
+        return unsafe { avx2::mono_to_stereo(input, output) };
+    }
+    scalar::mono_to_stereo(input, output)
+}
+
+/// 浮点样本量化为 `bits` 位有符号整数，规则同 `sample_format::quantize`
+pub fn quantize(input: &[f32], bits: u32, output: &mut [i32]) {
+    assert_eq!(input.len(), output.len());
+    #[cfg(target_arch = "x86_64")]
+    if has_avx2() {
+        // SAFETY: 同上
+        return unsafe { avx2::quantize(input, bits, output) };
+    }
+    scalar::quantize(input, bits, output)
+}
+
+/// 标量参考实现，也用于基准测试对比
+pub mod scalar {
+    use crate::output::sample_format;
+
+    pub fn scale(samples: &mut [f32], factor: f32) {
+        for sample in samples {
+            *sample *= factor;
+        }
+    }
+
+    pub fn peak(samples: &[f32]) -> f32 {
+        samples.iter().fold(0.0_f32, |max, s| max.max(s.abs()))
+    }
+
+    pub fn stereo_to_mono(input: &[f32], output: &mut [f32]) {
+        for (frame, out) in input.chunks_exact(2).zip(output) {
+            *out = (frame[0] + frame[1]) / 2.0;
+        }
+    }
+
+    pub fn mono_to_stereo(input: &[f32], output: &mut [f32]) {
+        for (&sample, out) in input.iter().zip(output.chunks_exact_mut(2)) {
+            out[0] = sample;
+            out[1] = sample;
+        }
+    }
+
+    pub fn quantize(input: &[f32], bits: u32, output: &mut [i32]) {
+        for (&sample, out) in input.iter().zip(output) {
+            *out = sample_format::quantize(sample, bits);
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+pub(crate) mod avx2 {
+    use super::scalar;
+    use std::arch::x86_64::*;
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn scale(samples: &mut [f32], factor: f32) {
+        let factor_v = _mm256_set1_ps(factor);
+        let mut chunks = samples.chunks_exact_mut(8);
+        for chunk in &mut chunks {
+            let ptr = chunk.as_mut_ptr();
+            _mm256_storeu_ps(ptr, _mm256_mul_ps(_mm256_loadu_ps(ptr), factor_v));
+        }
+        scalar::scale(chunks.into_remainder(), factor);
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn peak(samples: &[f32]) -> f32 {
+        // 清除符号位取绝对值；MAXPS 在任一操作数为 NaN 时返回第二个操作数，因此累加值放在第二位以忽略 NaN
+        let abs_mask = _mm256_castsi256_ps(_mm256_set1_epi32(0x7FFF_FFFF));
+        let mut max_v = _mm256_setzero_ps();
+        let chunks = samples.chunks_exact(8);
+        let tail = chunks.remainder();
+        for chunk in chunks {
+            let abs = _mm256_and_ps(_mm256_loadu_ps(chunk.as_ptr()), abs_mask);
+            max_v = _mm256_max_ps(abs, max_v);
+        }
+        let mut lanes = [0.0_f32; 8];
+        _mm256_storeu_ps(lanes.as_mut_ptr(), max_v);
+        lanes.iter().chain(tail).fold(0.0_f32, |max, s| max.max(s.abs()))
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn stereo_to_mono(input: &[f32], output: &mut [f32]) {
+        let half = _mm256_set1_ps(0.5);
+        let frames = output.len() / 8 * 8;
+        for i in (0..frames).step_by(8) {
+            let a = _mm256_loadu_ps(input.as_ptr().add(i * 2));
+            let b = _mm256_loadu_ps(input.as_ptr().add(i * 2 + 8));
+            // hadd 按 128 位通道交错两个输入的和：[a01 a23 b01 b23 | a45 a67 b45 b67]，再按 64 位重排成帧顺序
+            let sums = _mm256_hadd_ps(a, b);
+            let ordered = _mm256_castpd_ps(_mm256_permute4x64_pd::<0b11_01_10_00>(_mm256_castps_pd(sums)));
+            // 除以 2 与乘以 0.5 的结果完全相同
+            _mm256_storeu_ps(output.as_mut_ptr().add(i), _mm256_mul_ps(ordered, half));
+        }
+        scalar::stereo_to_mono(&input[frames * 2..], &mut output[frames..]);
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn mono_to_stereo(input: &[f32], output: &mut [f32]) {
+        let frames = input.len() / 8 * 8;
+        for i in (0..frames).step_by(8) {
+            let v = _mm256_loadu_ps(input.as_ptr().add(i));
+            // unpack 在 128 位通道内复制：lo = [0 0 1 1 | 4 4 5 5]，hi = [2 2 3 3 | 6 6 7 7]
+            let lo = _mm256_unpacklo_ps(v, v);
+            let hi = _mm256_unpackhi_ps(v, v);
+            let out = output.as_mut_ptr().add(i * 2);
+            _mm256_storeu_ps(out, _mm256_permute2f128_ps::<0x20>(lo, hi));
+            _mm256_storeu_ps(out.add(8), _mm256_permute2f128_ps::<0x31>(lo, hi));
+        }
+        scalar::mono_to_stereo(&input[frames..], &mut output[frames * 2..]);
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn quantize(input: &[f32], bits: u32, output: &mut [i32]) {
+        let full_scale = (1u64 << (bits - 1)) as f64;
+        let scale_v = _mm256_set1_pd(full_scale);
+        let low = _mm256_set1_pd(-full_scale);
+        let high = _mm256_set1_pd(full_scale - 1.0);
+        let half = _mm256_set1_pd(0.5);
+        let sign_mask = _mm256_set1_pd(-0.0);
+        let len = input.len() / 4 * 4;
+        for i in (0..len).step_by(4) {
+            let x = _mm256_mul_pd(_mm256_cvtps_pd(_mm_loadu_ps(input.as_ptr().add(i))), scale_v);
+            // NaN 置零，与标量 `as i32` 一致
+            let x = _mm256_and_pd(x, _mm256_cmp_pd::<_CMP_ORD_Q>(x, x));
+            // f32 样本乘以 2 的幂在 f64 中精确，加 ±0.5 后截断即为远离零的四舍五入
+            let rounded = _mm256_round_pd::<{ _MM_FROUND_TO_ZERO | _MM_FROUND_NO_EXC }>(_mm256_add_pd(
+                x,
+                _mm256_or_pd(_mm256_and_pd(x, sign_mask), half),
+            ));
+            let clamped = _mm256_min_pd(_mm256_max_pd(rounded, low), high);
+            _mm_storeu_si128(output.as_mut_ptr().add(i) as *mut __m128i, _mm256_cvttpd_epi32(clamped));
+        }
+        scalar::quantize(&input[len..], bits, &mut output[len..]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 覆盖向量主体和尾部，并包含取整边界、饱和、无穷大和 NaN
+    fn test_samples() -> Vec<f32> {
+        let mut samples: Vec<f32> = (0..1003).map(|i| (i as f32 * 0.37).sin() * 1.3).collect();
+        samples.extend([
+            0.5 / 32768.0,
+            -0.5 / 32768.0,
+            1.5 / 32768.0,
+            -2.5 / 128.0,
+            1.0,
+            -1.0,
+            f32::INFINITY,
+            f32::NEG_INFINITY,
+            f32::NAN,
+            -0.0,
+            f32::MIN_POSITIVE,
+        ]);
+        samples
+    }
+
+    #[test]
+    fn test_kernels_match_scalar() {
+        let samples = test_samples();
+
+        let mut expected = samples.clone();
+        scalar::scale(&mut expected, 0.7);
+        let mut actual = samples.clone();
+        scale(&mut actual, 0.7);
+        assert_eq!(to_bits(&expected), to_bits(&actual));
+
+        assert_eq!(peak(&samples[..1003]).to_bits(), scalar::peak(&samples[..1003]).to_bits());
+        assert_eq!(peak(&samples), f32::INFINITY);
+        assert_eq!(peak(&[f32::NAN, 0.25]), 0.25);
+        assert_eq!(peak(&[]), 0.0);
+
+        let stereo = &samples[..samples.len() / 2 * 2];
+        let mut expected = vec![0.0; stereo.len() / 2];
+        scalar::stereo_to_mono(stereo, &mut expected);
+        let mut actual = vec![0.0; stereo.len() / 2];
+        stereo_to_mono(stereo, &mut actual);
+        assert_eq!(to_bits(&expected), to_bits(&actual));
+
+        let mut expected = vec![0.0; samples.len() * 2];
+        scalar::mono_to_stereo(&samples, &mut expected);
+        let mut actual = vec![0.0; samples.len() * 2];
+        mono_to_stereo(&samples, &mut actual);
+        assert_eq!(to_bits(&expected), to_bits(&actual));
+
+        for bits in [8, 16, 24, 32] {
+            let mut expected = vec![0; samples.len()];
+            scalar::quantize(&samples, bits, &mut expected);
+            let mut actual = vec![0; samples.len()];
+            quantize(&samples, bits, &mut actual);
+            assert_eq!(expected, actual, "{} 位", bits);
+        }
+    }
+
+    fn to_bits(samples: &[f32]) -> Vec<u32> {
+        samples.iter().map(|s| s.to_bits()).collect()
+    }
+}
@@ -1,5 +1,6 @@
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
 use serde::{Deserialize, Serialize};
+use crate::audio::filter::FilterSettings;
 
 #[derive(Parser, Debug)]
 #[command(name = "audio-converter")]
@@ -11,33 +12,72 @@ AudioConverter - 音频文件转数组工具
 
 支持的音频格式：
   输入: MP3, WAV, FLAC, OGG, AAC
-  输出: f32, f64, i16, i32 数组格式
+  输出: f32, f64, i8, u8, i16, u16, i24, i32, q15, q31 等数组格式，WAV/FLAC 音频文件，
+        或 JSON/CSV/NPY/raw PCM 数据文件（按输出文件扩展名选择）
 
 使用示例：
   audio-converter -i music.mp3 -o output.rs
   audio-converter -i music.wav -o output.rs -f i16 -s 44100 -c 1
+  audio-converter -i music.wav -o output.rs -C config.toml -s source --normalize
+  audio-converter -i sounds/ -o src/sounds/ -f i16 --watch
   audio-converter -i music.flac -o output.rs -g 3.0 -v
+  audio-converter -i mic.wav -o output.rs --filter dcblock --filter highpass:80
+  audio-converter -i music.mp3 -o output.wav --wav-format pcm24
+  audio-converter -i music.mp3 -o output.rs --preview
+  audio-converter -i music.wav -o output.rs -f i16 --layout planar
+  audio-converter -i music.wav -o output.rs --float-format shortest --per-line 8
+  audio-converter -i music.wav -o sound.h -f i16 --template c_header.tpl
+  audio-converter -i music.wav -o sound.h --preset esp32-i2s-16k
+  audio-converter -i music.wav -o features.npy -s 16000 -c 1
+  audio-converter -i music.wav -o samples.raw -f i16 --endian big
+  audio-converter -i speech.wav -o mel.rs -s 16000 -c 1 --features log-mel --mel-bands 40
+  audio-converter info music.mp3
+  audio-converter analyze music.wav --json
+  audio-converter decode-array output.rs -o restored.wav
+  audio-converter config show
+  audio-converter build audio-assets.toml
 "#)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct Args {
-    /// 输入音频文件路径
+    /// 子命令（省略时执行转换）
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// 输入音频文件路径，或包含音频文件的目录
     #[arg(short, long)]
     pub input: Option<String>,
 
-    /// 输出文件路径
+    /// 输出文件路径（输入为目录时为输出目录）
     #[arg(short, long)]
     pub output: Option<String>,
 
+    /// 输入为目录时输出文件的扩展名（默认 rs）
+    #[arg(long, value_name = "EXT")]
+    pub output_ext: Option<String>,
+
+    /// 持续监视输入和配置文件，变化时重新转换对应的文件
+    #[arg(short, long)]
+    pub watch: bool,
+
+    /// 目录转换时忽略缓存重新转换所有文件，并覆盖被手动修改的输出
+    #[arg(long)]
+    pub force: bool,
+
+    /// 工作线程数，同时用于并发转换多个文件和分块处理单个文件（默认使用全部 CPU 核心）
+    #[arg(short, long, value_name = "N")]
+    pub jobs: Option<usize>,
+
     /// 输出数组格式
     #[arg(short, long)]
     pub format: Option<OutputFormat>,
 
-    /// 目标采样率 (Hz)
-    #[arg(short, long)]
-    pub sample_rate: Option<u32>,
+    /// 目标采样率 (Hz)，`source` 表示保持源文件采样率
+    #[arg(short, long, value_name = "HZ|source")]
+    pub sample_rate: Option<SourceOr<u32>>,
 
-    /// 声道数 (1=单声道, 2=立体声)
-    #[arg(short, long)]
-    pub channels: Option<u32>,
+    /// 声道数 (1=单声道, 2=立体声)，`source` 表示保持源文件声道数
+    #[arg(short, long, value_name = "N|source")]
+    pub channels: Option<SourceOr<u32>>,
 
     /// 音频开始时间 (秒)
     #[arg(long)]
@@ -48,10 +88,139 @@ pub struct Args {
     pub duration: Option<f64>,
 
     /// 音量增益 (dB)
-    #[arg(short, long)]
+    #[arg(short, long, allow_negative_numbers = true)]
     pub gain: Option<f32>,
 
-    /// 配置文件路径
+    /// 峰值归一化
+    #[arg(long, overrides_with = "no_normalize")]
+    pub normalize: bool,
+
+    /// 关闭峰值归一化（覆盖配置文件）
+    #[arg(long, overrides_with = "normalize")]
+    pub no_normalize: bool,
+
+    /// 滤波器，格式为 类型[:截止频率[:Q[:增益dB]]]，可重复指定
+    /// (类型: lowpass, highpass, bandpass, lowshelf, highshelf, notch, dcblock)
+    #[arg(long = "filter", value_name = "FILTER", conflicts_with = "no_filters")]
+    pub filters: Vec<FilterSettings>,
+
+    /// 清空配置文件中的滤波器
+    #[arg(long)]
+    pub no_filters: bool,
+
+    /// 处理步骤顺序，逗号分隔 (gain, filter, resample, channels, normalize)，`default` 恢复默认顺序
+    #[arg(long, value_name = "STEPS")]
+    pub chain: Option<String>,
+
+    /// 音频文件输出格式（输出为 .wav/.flac 或使用 --preview 时生效）
+    #[arg(long)]
+    pub wav_format: Option<WavFormat>,
+
+    /// 数组的声道排列方式
+    #[arg(long)]
+    pub layout: Option<ArrayLayout>,
+
+    /// 浮点数组的数值写法
+    #[arg(long)]
+    pub float_format: Option<FloatFormat>,
+
+    /// 浮点数保留的小数位数（用于 --float-format fixed）
+    #[arg(long)]
+    pub precision: Option<usize>,
+
+    /// 整数数组使用十六进制字面量
+    #[arg(long, overrides_with = "no_hex")]
+    pub hex: bool,
+
+    /// 整数数组使用十进制字面量（覆盖配置文件）
+    #[arg(long, overrides_with = "hex")]
+    pub no_hex: bool,
+
+    /// 每行输出的数值个数，大于 1 时按列对齐
+    #[arg(long, value_name = "N")]
+    pub per_line: Option<usize>,
+
+    /// 使用自定义模板文件生成输出
+    #[arg(long, value_name = "FILE", conflicts_with = "no_template")]
+    pub template: Option<String>,
+
+    /// 不使用模板（覆盖配置文件或预设中的模板）
+    #[arg(long)]
+    pub no_template: bool,
+
+    /// 生成代码中的标识符名称（数组常量前缀、模板中的 name）
+    #[arg(long, value_name = "IDENT")]
+    pub name: Option<String>,
+
+    /// 输出设置中的数组类型 (output_settings.array_type)
+    #[arg(long, value_name = "TYPE")]
+    pub array_type: Option<String>,
+
+    /// 在数组文件开头写入元数据注释（默认开启）
+    #[arg(long, overrides_with = "no_metadata")]
+    pub metadata: bool,
+
+    /// 不写入元数据注释
+    #[arg(long, overrides_with = "metadata")]
+    pub no_metadata: bool,
+
+    /// 启用压缩 (output_settings.compress)
+    #[arg(long, overrides_with = "no_compress")]
+    pub compress: bool,
+
+    /// 关闭压缩
+    #[arg(long, overrides_with = "compress")]
+    pub no_compress: bool,
+
+    /// raw PCM / NPY 输出的字节序
+    #[arg(long)]
+    pub endian: Option<Endianness>,
+
+    /// CSV 输出增加时间戳列（秒）
+    #[arg(long, overrides_with = "no_timestamps")]
+    pub timestamps: bool,
+
+    /// CSV 输出不含时间戳列（覆盖配置文件）
+    #[arg(long, overrides_with = "timestamps")]
+    pub no_timestamps: bool,
+
+    /// 输出频谱特征而不是样本 (stft, mel, log-mel, mfcc)
+    #[arg(long, conflicts_with = "no_features")]
+    pub features: Option<FeatureKind>,
+
+    /// 关闭配置文件中的特征提取，输出样本
+    #[arg(long)]
+    pub no_features: bool,
+
+    /// 特征提取的 FFT 点数（2 的幂）
+    #[arg(long)]
+    pub fft_size: Option<usize>,
+
+    /// 特征提取的帧移（样本数）
+    #[arg(long)]
+    pub hop: Option<usize>,
+
+    /// 特征提取的窗函数
+    #[arg(long)]
+    pub window: Option<WindowType>,
+
+    /// 梅尔滤波器个数
+    #[arg(long)]
+    pub mel_bands: Option<usize>,
+
+    /// MFCC 系数个数
+    #[arg(long)]
+    pub mfcc: Option<usize>,
+
+    /// 同时在输出文件旁写出一个 WAV 文件用于试听
+    #[arg(long)]
+    pub preview: bool,
+
+    /// 使用命名预设（esp32-i2s-16k、web-audio、ml-16k-mono、gba-8bit 或配置文件中的自定义预设），其他参数可覆盖预设
+    #[arg(long, value_name = "NAME")]
+    pub preset: Option<String>,
+
+    /// 配置文件路径（.json / .toml / .yaml，按扩展名选择格式）
     #[arg(short = 'C', long)]
     pub config: Option<String>,
 
@@ -60,15 +229,500 @@ pub struct Args {
     pub verbose: bool,
 }
 
+/// 可以显式设为「保持源文件」的参数值，命令行中写作 `source` 或 `none`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceOr<T> {
+    Source,
+    Value(T),
+}
+
+impl<T: Copy> SourceOr<T> {
+    /// `Source` 对应配置中的 `None`
+    pub fn value(&self) -> Option<T> {
+        match self {
+            SourceOr::Source => None,
+            SourceOr::Value(value) => Some(*value),
+        }
+    }
+}
+
+impl<T> std::str::FromStr for SourceOr<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "source" | "none" => Ok(SourceOr::Source),
+            _ => s
+                .parse()
+                .map(SourceOr::Value)
+                .map_err(|e| format!("{}（或使用 source 保持源文件）", e)),
+        }
+    }
+}
+
+/// 一对开关参数（`--x` / `--no-x`）合并为可选的布尔值，都未指定时为 `None`
+pub fn flag_pair(enable: bool, disable: bool) -> Option<bool> {
+    match (enable, disable) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// 查看音频文件信息（仅探测文件头，不进行转换）
+    #[command(visible_alias = "probe")]
+    Info(InfoArgs),
+    /// 分析音频统计信息（峰值、RMS、响度等）
+    Analyze(AnalyzeArgs),
+    /// 将数组源码文件还原为音频文件
+    DecodeArray(DecodeArrayArgs),
+    /// 生成波形峰值数据（每个点的 min/max，可选 RMS），用于绘制波形缩略图
+    Peaks(PeaksArgs),
+    /// 将波形图或频谱图渲染为 PNG 图片
+    Render(RenderArgs),
+    /// 按项目清单（默认 audio-assets.toml）并行转换所有音频资源
+    Build(BuildArgs),
+    /// 配置相关操作
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(clap::Args, Debug)]
+pub struct BuildArgs {
+    /// 构建清单文件路径
+    #[arg(default_value = crate::cli::build::MANIFEST_NAME)]
+    pub manifest: String,
+
+    /// 忽略缓存重新转换所有文件，并覆盖被手动修改的输出
+    #[arg(long)]
+    pub force: bool,
+
+    /// 只列出展开后的任务，不进行转换
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// 工作线程数（默认使用全部 CPU 核心）
+    #[arg(short, long, value_name = "N")]
+    pub jobs: Option<usize>,
+
+    /// 详细输出
+    #[arg(short, long)]
+    pub verbose: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// 显示合并后的有效配置以及每个字段的来源
+    Show(ConfigShowArgs),
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ConfigShowArgs {
+    /// 额外加载的配置文件（与转换命令的 -C 相同）
+    #[arg(short = 'C', long)]
+    pub config: Option<String>,
+
+    /// 应用命名预设
+    #[arg(long, value_name = "NAME")]
+    pub preset: Option<String>,
+
+    /// 以 JSON 格式输出
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct InfoArgs {
+    /// 输入音频文件路径
+    pub input: String,
+
+    /// 以 JSON 格式输出
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct AnalyzeArgs {
+    /// 输入音频文件路径
+    pub input: String,
+
+    /// 以 JSON 格式输出
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct DecodeArrayArgs {
+    /// 输入数组文件路径（ArrayWriter 的输出或 C/Rust/Python 数组）
+    pub input: String,
+
+    /// 输出音频文件路径 (.wav 或 .flac)
+    #[arg(short, long)]
+    pub output: String,
+
+    /// 数组元素格式（覆盖文件元数据）
+    #[arg(short, long)]
+    pub format: Option<OutputFormat>,
+
+    /// 采样率 (Hz)（覆盖文件元数据）
+    #[arg(short, long)]
+    pub sample_rate: Option<u32>,
+
+    /// 声道数（覆盖文件元数据）
+    #[arg(short, long)]
+    pub channels: Option<u32>,
+
+    /// 数组的声道排列方式（覆盖文件元数据）
+    #[arg(long)]
+    pub layout: Option<ArrayLayout>,
+
+    /// 输出音频的样本格式
+    #[arg(long)]
+    pub wav_format: Option<WavFormat>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct PeaksArgs {
+    /// 输入音频文件路径
+    pub input: String,
+
+    /// 输出文件路径（.json 为 audiowaveform 风格的 JSON，其他扩展名为紧凑数组）
+    #[arg(short, long)]
+    pub output: String,
+
+    /// 每个点覆盖的帧数（默认 256）
+    #[arg(long, value_name = "N", conflicts_with = "points")]
+    pub samples_per_pixel: Option<usize>,
+
+    /// 目标点数，按音频长度自动计算每个点覆盖的帧数
+    #[arg(long, value_name = "N")]
+    pub points: Option<usize>,
+
+    /// 同时输出每个点的 RMS
+    #[arg(long)]
+    pub rms: bool,
+
+    /// 峰值量化位数（8 或 16）
+    #[arg(short, long, default_value_t = 16)]
+    pub bits: u32,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct RenderArgs {
+    /// 输入音频文件路径
+    pub input: String,
+
+    /// 输出 PNG 文件路径
+    #[arg(short, long)]
+    pub output: String,
+
+    /// 渲染内容
+    #[arg(short, long, default_value = "waveform")]
+    pub mode: RenderMode,
+
+    /// 图像宽度（像素）
+    #[arg(long, default_value_t = 800)]
+    pub width: u32,
+
+    /// 波形图中每个声道的高度，或频谱图的图像高度（像素）
+    #[arg(long, default_value_t = 200)]
+    pub height: u32,
+
+    /// 波形颜色 (#RRGGBB)
+    #[arg(long, default_value = "#1E90FF")]
+    pub color: String,
+
+    /// 背景颜色 (#RRGGBB)
+    #[arg(long, default_value = "#FFFFFF")]
+    pub background: String,
+
+    /// 渲染前重采样到指定采样率 (Hz)
+    #[arg(short, long)]
+    pub sample_rate: Option<u32>,
+
+    /// 渲染前转换为指定声道数
+    #[arg(short, long)]
+    pub channels: Option<u32>,
+
+    /// 频谱图的 FFT 点数（2 的幂）
+    #[arg(long, default_value_t = 512)]
+    pub fft_size: usize,
+
+    /// 频谱图的帧移（样本数）
+    #[arg(long, default_value_t = 128)]
+    pub hop: usize,
+
+    /// 频谱图的窗函数
+    #[arg(long, default_value = "hann")]
+    pub window: WindowType,
+}
+
+#[derive(ValueEnum, Clone, Debug, PartialEq, Eq, Copy, Default)]
+pub enum RenderMode {
+    /// 每个声道一条轨道的波形图
+    #[default]
+    Waveform,
+    /// 频谱图（多声道先混合为单声道）
+    Spectrogram,
+}
+
 #[derive(ValueEnum, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Copy)]
 pub enum OutputFormat {
     /// 32位浮点
+    #[serde(alias = "f32")]
     F32,
-    /// 64位浮点  
+    /// 64位浮点
+    #[serde(alias = "f64")]
     F64,
+    /// 8位有符号整数
+    #[serde(alias = "i8")]
+    I8,
+    /// 8位无符号整数（偏移二进制，128 为静音）
+    #[serde(alias = "u8")]
+    U8,
     /// 16位整数
+    #[serde(alias = "i16")]
     I16,
+    /// 16位无符号整数（偏移二进制，32768 为静音）
+    #[serde(alias = "u16")]
+    U16,
+    /// 24位整数，存放在 i32 中
+    #[serde(alias = "i24")]
+    I24,
+    /// 24位整数，按小端序打包为每样本 3 字节
+    #[serde(alias = "i24-packed", alias = "i24_packed")]
+    I24Packed,
     /// 32位整数
+    #[serde(alias = "i32")]
     I32,
+    /// Q15 定点数 (i16)
+    #[serde(alias = "q15")]
+    Q15,
+    /// Q31 定点数 (i32)
+    #[serde(alias = "q31")]
+    Q31,
 }
 
+impl OutputFormat {
+    /// 对应的 C 类型
+    pub fn c_type(&self) -> &'static str {
+        match self {
+            OutputFormat::F32 => "float",
+            OutputFormat::F64 => "double",
+            OutputFormat::I8 => "int8_t",
+            OutputFormat::U8 | OutputFormat::I24Packed => "uint8_t",
+            OutputFormat::I16 | OutputFormat::Q15 => "int16_t",
+            OutputFormat::U16 => "uint16_t",
+            OutputFormat::I24 | OutputFormat::I32 | OutputFormat::Q31 => "int32_t",
+        }
+    }
+
+    /// 写入元数据和命令行使用的小写名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutputFormat::F32 => "f32",
+            OutputFormat::F64 => "f64",
+            OutputFormat::I8 => "i8",
+            OutputFormat::U8 => "u8",
+            OutputFormat::I16 => "i16",
+            OutputFormat::U16 => "u16",
+            OutputFormat::I24 => "i24",
+            OutputFormat::I24Packed => "i24-packed",
+            OutputFormat::I32 => "i32",
+            OutputFormat::Q15 => "q15",
+            OutputFormat::Q31 => "q31",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        <Self as ValueEnum>::from_str(name, true).ok()
+    }
+
+    /// 数组元素使用的 Rust 类型
+    pub fn rust_type(&self) -> &'static str {
+        match self {
+            OutputFormat::F32 => "f32",
+            OutputFormat::F64 => "f64",
+            OutputFormat::I8 => "i8",
+            OutputFormat::U8 | OutputFormat::I24Packed => "u8",
+            OutputFormat::I16 | OutputFormat::Q15 => "i16",
+            OutputFormat::U16 => "u16",
+            OutputFormat::I24 | OutputFormat::I32 | OutputFormat::Q31 => "i32",
+        }
+    }
+
+    /// 二进制输出（raw PCM、NPY）中每个样本占用的字节数
+    pub fn bytes_per_sample(&self) -> usize {
+        match self {
+            OutputFormat::I8 | OutputFormat::U8 => 1,
+            OutputFormat::I16 | OutputFormat::U16 | OutputFormat::Q15 => 2,
+            OutputFormat::I24Packed => 3,
+            OutputFormat::F32 | OutputFormat::I24 | OutputFormat::I32 | OutputFormat::Q31 => 4,
+            OutputFormat::F64 => 8,
+        }
+    }
+
+    /// 整数格式的有效位数，浮点格式返回 None
+    pub fn bits(&self) -> Option<u32> {
+        match self {
+            OutputFormat::F32 | OutputFormat::F64 => None,
+            OutputFormat::I8 | OutputFormat::U8 => Some(8),
+            OutputFormat::I16 | OutputFormat::U16 | OutputFormat::Q15 => Some(16),
+            OutputFormat::I24 | OutputFormat::I24Packed => Some(24),
+            OutputFormat::I32 | OutputFormat::Q31 => Some(32),
+        }
+    }
+
+    /// 是否为无符号的偏移二进制格式
+    pub fn is_unsigned(&self) -> bool {
+        matches!(self, OutputFormat::U8 | OutputFormat::U16)
+    }
+}
+#[derive(ValueEnum, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Copy, Default)]
+pub enum ArrayLayout {
+    /// 交错排列：L R L R ...
+    #[default]
+    #[serde(alias = "interleaved")]
+    Interleaved,
+    /// 平面排列：每个声道一段连续数据
+    #[serde(alias = "planar")]
+    Planar,
+    /// 每个声道一个独立命名的数组（AUDIO_LEFT、AUDIO_RIGHT ...）
+    #[serde(alias = "per-channel", alias = "per_channel")]
+    PerChannel,
+    /// 二维数组 [[T; 声道数]; 帧数]
+    #[value(name = "2d")]
+    #[serde(alias = "2d")]
+    TwoD,
+}
+
+impl ArrayLayout {
+    /// 写入元数据和命令行使用的名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            ArrayLayout::Interleaved => "interleaved",
+            ArrayLayout::Planar => "planar",
+            ArrayLayout::PerChannel => "per-channel",
+            ArrayLayout::TwoD => "2d",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        <Self as ValueEnum>::from_str(name, true).ok()
+    }
+}
+
+#[derive(ValueEnum, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Copy, Default)]
+pub enum FloatFormat {
+    /// 固定小数位数
+    #[default]
+    #[serde(alias = "fixed")]
+    Fixed,
+    /// 能精确还原的最短表示
+    #[serde(alias = "shortest")]
+    Shortest,
+    /// C99 十六进制浮点字面量，例如 0x1.8p-1（仅用于 C 模板）
+    #[serde(alias = "hex-float", alias = "hex_float")]
+    HexFloat,
+    /// 位模式，例如 f32::from_bits(0x3F400000)（仅用于 Rust 输出）
+    #[serde(alias = "bits")]
+    Bits,
+}
+
+#[derive(ValueEnum, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Copy, Default)]
+pub enum Endianness {
+    /// 小端序
+    #[default]
+    #[serde(alias = "little")]
+    Little,
+    /// 大端序
+    #[serde(alias = "big")]
+    Big,
+}
+
+#[derive(ValueEnum, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Copy)]
+pub enum FeatureKind {
+    /// STFT 幅度谱
+    #[serde(alias = "stft")]
+    Stft,
+    /// 梅尔功率谱
+    #[serde(alias = "mel")]
+    Mel,
+    /// 对数梅尔谱（自然对数）
+    #[serde(alias = "log-mel", alias = "log_mel")]
+    LogMel,
+    /// 梅尔频率倒谱系数
+    #[serde(alias = "mfcc")]
+    Mfcc,
+}
+
+impl FeatureKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FeatureKind::Stft => "stft",
+            FeatureKind::Mel => "mel",
+            FeatureKind::LogMel => "log-mel",
+            FeatureKind::Mfcc => "mfcc",
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Copy, Default)]
+pub enum WindowType {
+    /// 汉宁窗
+    #[default]
+    #[serde(alias = "hann")]
+    Hann,
+    /// 汉明窗
+    #[serde(alias = "hamming")]
+    Hamming,
+    /// 矩形窗
+    #[serde(alias = "rect")]
+    Rect,
+}
+
+impl WindowType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WindowType::Hann => "hann",
+            WindowType::Hamming => "hamming",
+            WindowType::Rect => "rect",
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Copy)]
+pub enum WavFormat {
+    /// 8位整数
+    #[serde(alias = "pcm8")]
+    Pcm8,
+    /// 16位整数
+    #[serde(alias = "pcm16")]
+    Pcm16,
+    /// 24位整数
+    #[serde(alias = "pcm24")]
+    Pcm24,
+    /// 32位整数
+    #[serde(alias = "pcm32")]
+    Pcm32,
+    /// 32位浮点
+    #[serde(alias = "float32")]
+    Float32,
+}
+
+impl WavFormat {
+    pub fn bits_per_sample(&self) -> u32 {
+        match self {
+            WavFormat::Pcm8 => 8,
+            WavFormat::Pcm16 => 16,
+            WavFormat::Pcm24 => 24,
+            WavFormat::Pcm32 | WavFormat::Float32 => 32,
+        }
+    }
+}
@@ -0,0 +1,325 @@
+//! `build` 子命令使用的项目清单
+//!
+//! 清单为 TOML 文件，`[defaults]` 中是所有任务共享的配置（任意 `Config` 字段），
+//! 每个 `[[jobs]]` 指定输入（路径或通配符）、输出路径、可选的标识符名称和预设，
+//! 其余字段作为该任务对配置的覆盖。合并顺序为 默认值 → `[defaults]` → 预设 → 任务字段。
+//! 相对路径（包括 `output_settings.template`）均相对于清单文件所在目录。
+//!
+//! ```toml
+//! [defaults]
+//! output_format = "i16"
+//! sample_rate = 16000
+//!
+//! [[jobs]]
+//! input = "sfx/*.wav"
+//! output = "src/sounds/{stem}.h"
+//! name = "{stem}"
+//! output_settings = { template = "builtin:c-header" }
+//! ```
+
+use crate::cli::convert::ConvertJob;
+use crate::config::layers::merge_values;
+use crate::config::settings::Config;
+use crate::output::template;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use anyhow::Result;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// `build` 默认读取的清单文件名
+pub const MANIFEST_NAME: &str = "audio-assets.toml";
+/// 输出路径和名称中替换为输入文件名（不含扩展名）的占位符
+const STEM_PLACEHOLDER: &str = "{stem}";
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawManifest {
+    #[serde(default)]
+    defaults: Map<String, Value>,
+    #[serde(default)]
+    jobs: Vec<Map<String, Value>>,
+}
+
+/// 清单中的一个任务
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobSpec {
+    /// 输入文件路径或通配符
+    pub input: String,
+    /// 输出路径，通配符输入时必须包含 `{stem}`
+    pub output: String,
+    /// 标识符名称（`output_settings.name`），可包含 `{stem}`
+    pub name: Option<String>,
+    pub preset: Option<String>,
+    /// 覆盖共享配置的字段
+    pub overrides: Map<String, Value>,
+}
+
+/// 展开后的单个转换任务及其有效配置
+#[derive(Debug, Clone)]
+pub struct BuildTask {
+    pub job: ConvertJob,
+    pub config: Config,
+}
+
+#[derive(Debug, Clone)]
+pub struct BuildManifest {
+    /// 清单所在目录，相对路径的基准
+    pub root: PathBuf,
+    pub defaults: Map<String, Value>,
+    pub jobs: Vec<JobSpec>,
+}
+
+impl BuildManifest {
+    pub fn from_file(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("无法读取构建清单 {}: {}", path, e))?;
+        let root = Path::new(path).parent().map(Path::to_path_buf).unwrap_or_default();
+        Self::parse(&content, &root).map_err(|e| anyhow::anyhow!("构建清单 {} 无效: {}", path, e))
+    }
+
+    /// 解析清单文本，`root` 为相对路径的基准目录
+    pub fn parse(content: &str, root: &Path) -> Result<Self> {
+        let raw: RawManifest = toml::from_str(content)?;
+        let jobs = raw
+            .jobs
+            .into_iter()
+            .enumerate()
+            .map(|(index, fields)| parse_job(fields).map_err(|e| anyhow::anyhow!("jobs[{}]: {}", index, e)))
+            .collect::<Result<Vec<_>>>()?;
+        if jobs.is_empty() {
+            anyhow::bail!("没有任何任务（使用 [[jobs]] 添加）");
+        }
+
+        let manifest = Self {
+            root: root.to_path_buf(),
+            defaults: raw.defaults,
+            jobs,
+        };
+        manifest.shared_config().map_err(|e| anyhow::anyhow!("defaults: {}", e))?;
+        Ok(manifest)
+    }
+
+    /// 展开通配符并计算每个任务的配置，多个任务输出到同一文件时报错
+    pub fn tasks(&self) -> Result<Vec<BuildTask>> {
+        let mut tasks = Vec::new();
+        for (index, spec) in self.jobs.iter().enumerate() {
+            let expanded = self
+                .expand(spec)
+                .map_err(|e| anyhow::anyhow!("jobs[{}] ({}): {}", index, spec.input, e))?;
+            tasks.extend(expanded);
+        }
+
+        let mut outputs = BTreeSet::new();
+        for task in &tasks {
+            if !outputs.insert(&task.job.output) {
+                anyhow::bail!("多个任务输出到同一个文件: {}", task.job.output.display());
+            }
+        }
+        Ok(tasks)
+    }
+
+    /// 默认值与 `[defaults]` 合并后的配置
+    fn shared_config(&self) -> Result<Config> {
+        let mut value = serde_json::to_value(Config::default())?;
+        merge_values(&mut value, Value::Object(self.defaults.clone()));
+        let config: Config = serde_json::from_value(value)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// 任务的有效配置（尚未替换名称中的占位符）
+    pub fn job_config(&self, spec: &JobSpec) -> Result<Config> {
+        let mut config = self.shared_config()?;
+        if let Some(preset) = &spec.preset {
+            config.find_preset(preset)?.apply(&mut config);
+        }
+        let mut value = serde_json::to_value(&config)?;
+        merge_values(&mut value, Value::Object(spec.overrides.clone()));
+        let mut config: Config = serde_json::from_value(value)?;
+        config.validate()?;
+
+        // 模板文件与输入输出一样相对于清单目录，内置模板不是路径
+        if let Some(template_path) = &mut config.output_settings.template {
+            if !template_path.starts_with(template::BUILTIN_PREFIX) && Path::new(template_path.as_str()).is_relative() {
+                *template_path = self.root.join(&*template_path).to_string_lossy().into_owned();
+            }
+        }
+        Ok(config)
+    }
+
+    fn expand(&self, spec: &JobSpec) -> Result<Vec<BuildTask>> {
+        let config = self.job_config(spec)?;
+        let inputs = if is_pattern(&spec.input) {
+            if !spec.output.contains(STEM_PLACEHOLDER) {
+                anyhow::bail!("通配符输入的输出路径必须包含 {}", STEM_PLACEHOLDER);
+            }
+            let pattern = if Path::new(&spec.input).is_absolute() {
+                spec.input.clone()
+            } else {
+                let root = glob::Pattern::escape(&self.root.to_string_lossy());
+                if root.is_empty() { spec.input.clone() } else { format!("{}/{}", root, spec.input) }
+            };
+            let mut inputs = glob::glob(&pattern)?
+                .collect::<std::result::Result<Vec<_>, _>>()?
+                .into_iter()
+                .filter(|path| path.is_file())
+                .collect::<Vec<_>>();
+            inputs.sort();
+            if inputs.is_empty() {
+                anyhow::bail!("没有匹配的文件");
+            }
+            inputs
+        } else {
+            vec![self.root.join(&spec.input)]
+        };
+
+        Ok(inputs
+            .into_iter()
+            .map(|input| {
+                let stem = input.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+                let mut config = config.clone();
+                if let Some(name) = &spec.name {
+                    config.output_settings.name = Some(name.replace(STEM_PLACEHOLDER, &stem));
+                }
+                let output = self.root.join(spec.output.replace(STEM_PLACEHOLDER, &stem));
+                BuildTask {
+                    job: ConvertJob { input, output },
+                    config,
+                }
+            })
+            .collect())
+    }
+}
+
+fn parse_job(mut fields: Map<String, Value>) -> Result<JobSpec> {
+    let mut take_string = |key: &str| -> Result<Option<String>> {
+        match fields.remove(key) {
+            None => Ok(None),
+            Some(Value::String(value)) => Ok(Some(value)),
+            Some(other) => anyhow::bail!("{} 必须是字符串，当前为 {}", key, other),
+        }
+    };
+    let input = take_string("input")?.ok_or_else(|| anyhow::anyhow!("缺少 input"))?;
+    let output = take_string("output")?.ok_or_else(|| anyhow::anyhow!("缺少 output"))?;
+    let name = take_string("name")?;
+    let preset = take_string("preset")?;
+    Ok(JobSpec {
+        input,
+        output,
+        name,
+        preset,
+        overrides: fields,
+    })
+}
+
+fn is_pattern(path: &str) -> bool {
+    path.contains(['*', '?', '['])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::args::OutputFormat;
+    use tempfile::TempDir;
+
+    const MANIFEST: &str = r#"
+[defaults]
+output_format = "i16"
+sample_rate = 16000
+output_settings = { values_per_line = 8 }
+
+[[jobs]]
+input = "sfx/*.wav"
+output = "out/{stem}.h"
+name = "sfx_{stem}"
+gain = -3.0
+
+[[jobs]]
+input = "music/theme.flac"
+output = "out/theme.rs"
+preset = "web-audio"
+output_settings = { layout = "planar" }
+"#;
+
+    fn setup() -> TempDir {
+        let root = TempDir::new().unwrap();
+        std::fs::create_dir_all(root.path().join("sfx")).unwrap();
+        for name in ["jump.wav", "coin.wav", "notes.txt"] {
+            std::fs::write(root.path().join("sfx").join(name), b"").unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn test_expand_jobs() {
+        let root = setup();
+        let manifest = BuildManifest::parse(MANIFEST, root.path()).unwrap();
+        let tasks = manifest.tasks().unwrap();
+        assert_eq!(tasks.len(), 3);
+
+        // 通配符按文件名排序展开，{stem} 替换到输出路径和名称
+        assert_eq!(tasks[0].job.input, root.path().join("sfx/coin.wav"));
+        assert_eq!(tasks[0].job.output, root.path().join("out/coin.h"));
+        assert_eq!(tasks[1].config.output_settings.name.as_deref(), Some("sfx_jump"));
+        assert_eq!(tasks[1].config.gain, -3.0);
+        assert_eq!(tasks[1].config.output_format, OutputFormat::I16);
+        assert_eq!(tasks[1].config.output_settings.values_per_line, 8);
+
+        // 预设覆盖共享配置，任务字段再覆盖预设，嵌套表逐字段合并
+        let theme = &tasks[2].config;
+        assert_eq!(theme.output_format, OutputFormat::F32);
+        assert_eq!(theme.sample_rate, Some(44100));
+        assert_eq!(theme.output_settings.layout, crate::cli::args::ArrayLayout::Planar);
+        assert_eq!(theme.output_settings.values_per_line, 8);
+        assert_eq!(theme.gain, 0.0);
+    }
+
+    #[test]
+    fn test_template_paths_are_relative_to_manifest() {
+        let root = setup();
+        let manifest = BuildManifest::parse(
+            r#"
+[defaults]
+output_settings = { template = "templates/array.tpl" }
+
+[[jobs]]
+input = "sfx/*.wav"
+output = "out/{stem}.h"
+
+[[jobs]]
+input = "music/theme.flac"
+output = "out/theme.h"
+output_settings = { template = "builtin:c-header" }
+"#,
+            root.path(),
+        )
+        .unwrap();
+        let tasks = manifest.tasks().unwrap();
+
+        let expected = root.path().join("templates/array.tpl");
+        assert_eq!(tasks[0].config.output_settings.template.as_deref(), expected.to_str());
+        assert_eq!(tasks[2].config.output_settings.template.as_deref(), Some("builtin:c-header"));
+    }
+
+    #[test]
+    fn test_manifest_errors() {
+        let root = setup();
+        let error = |content: &str| match BuildManifest::parse(content, root.path()).and_then(|m| m.tasks()) {
+            Ok(_) => panic!("应当失败: {}", content),
+            Err(e) => e.to_string(),
+        };
+
+        assert!(error("").contains("没有任何任务"));
+        assert!(error("[[jobs]]\noutput = \"a.rs\"").contains("jobs[0]: 缺少 input"));
+        assert!(error("[[jobs]]\ninput = \"a.wav\"\noutput = \"a.rs\"\ngian = 1.0").contains("gian"));
+        assert!(error("[defaults]\nchannels = 0\n[[jobs]]\ninput = \"a.wav\"\noutput = \"a.rs\"").contains("defaults"));
+        assert!(error("[[jobs]]\ninput = \"sfx/*.wav\"\noutput = \"out.rs\"").contains("{stem}"));
+        assert!(error("[[jobs]]\ninput = \"none/*.wav\"\noutput = \"{stem}.rs\"").contains("没有匹配的文件"));
+        assert!(error("[[jobs]]\ninput = \"a.wav\"\noutput = \"a.rs\"\npreset = \"nope\"").contains("未知的预设"));
+        assert!(error(
+            "[[jobs]]\ninput = \"a.wav\"\noutput = \"same.rs\"\n[[jobs]]\ninput = \"b.wav\"\noutput = \"same.rs\""
+        )
+        .contains("同一个文件"));
+    }
+}
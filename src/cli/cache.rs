@@ -0,0 +1,237 @@
+//! 目录转换的增量缓存
+//!
+//! 输出目录中的清单文件记录每个输出对应的输入内容哈希、配置哈希和输出内容哈希。
+//! 输入和有效配置都未变化、输出文件也未被改动时跳过该文件；
+//! 输出被删除时重新生成，被手动修改时不覆盖（除非使用 `--force`）。
+
+use crate::cli::convert::{ConvertJob, ConvertOptions};
+use crate::config::settings::Config;
+use crate::output::template;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// 清单文件名
+pub const MANIFEST_NAME: &str = ".audio-converter-cache.json";
+/// 清单格式版本，格式不兼容时整体失效
+const MANIFEST_VERSION: u32 = 1;
+
+/// 一个输出文件的缓存记录，哈希均为 SHA-256 十六进制字符串
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntry {
+    pub input: String,
+    pub input_hash: String,
+    pub config_hash: String,
+    pub output_hash: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Manifest {
+    version: u32,
+    /// 键为相对于输出目录的输出路径
+    entries: BTreeMap<String, CacheEntry>,
+}
+
+/// 任务相对于缓存的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    /// 输入、配置和输出都未变化
+    Fresh,
+    /// 没有记录，或输入、配置有变化
+    Stale,
+    /// 输出文件已被删除
+    OutputMissing,
+    /// 输出文件在上次生成后被修改
+    OutputModified,
+}
+
+/// 输出目录中的缓存清单
+#[derive(Debug)]
+pub struct Cache {
+    dir: PathBuf,
+    manifest: Manifest,
+}
+
+impl Cache {
+    /// 读取输出目录中的清单；不存在或无法解析时从空清单开始
+    pub fn load(dir: &Path) -> Self {
+        let path = dir.join(MANIFEST_NAME);
+        let manifest = match std::fs::read_to_string(&path) {
+            Ok(content) => match serde_json::from_str::<Manifest>(&content) {
+                Ok(manifest) if manifest.version == MANIFEST_VERSION => manifest,
+                Ok(_) => Manifest::default(),
+                Err(e) => {
+                    eprintln!("警告: 缓存清单 {} 无效，将重新转换所有文件: {}", path.display(), e);
+                    Manifest::default()
+                }
+            },
+            Err(_) => Manifest::default(),
+        };
+        Self {
+            dir: dir.to_path_buf(),
+            manifest,
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let manifest = serde_json::json!({
+            "version": MANIFEST_VERSION,
+            "entries": self.manifest.entries,
+        });
+        let path = self.dir.join(MANIFEST_NAME);
+        std::fs::write(&path, serde_json::to_string_pretty(&manifest)?)
+            .map_err(|e| anyhow::anyhow!("无法写入缓存清单 {}: {}", path.display(), e))
+    }
+
+    pub fn entry(&self, job: &ConvertJob) -> Option<&CacheEntry> {
+        self.manifest.entries.get(&self.key(job))
+    }
+
+    /// 比较输入哈希、配置哈希和当前的输出文件
+    pub fn status(&self, job: &ConvertJob, input_hash: &str, config_hash: &str) -> Result<CacheStatus> {
+        let Some(entry) = self.entry(job) else {
+            return Ok(CacheStatus::Stale);
+        };
+        if !job.output.exists() {
+            return Ok(CacheStatus::OutputMissing);
+        }
+        if hash_file(&job.output)? != entry.output_hash {
+            return Ok(CacheStatus::OutputModified);
+        }
+        if entry.input_hash == input_hash && entry.config_hash == config_hash {
+            Ok(CacheStatus::Fresh)
+        } else {
+            Ok(CacheStatus::Stale)
+        }
+    }
+
+    /// 转换成功后记录当前的哈希
+    pub fn record(&mut self, job: &ConvertJob, input_hash: &str, config_hash: &str) -> Result<()> {
+        let entry = CacheEntry {
+            input: job.input.to_string_lossy().into_owned(),
+            input_hash: input_hash.to_string(),
+            config_hash: config_hash.to_string(),
+            output_hash: hash_file(&job.output)?,
+        };
+        let key = self.key(job);
+        self.manifest.entries.insert(key, entry);
+        Ok(())
+    }
+
+    fn key(&self, job: &ConvertJob) -> String {
+        job.output
+            .strip_prefix(&self.dir)
+            .unwrap_or(&job.output)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+}
+
+/// 文件内容的 SHA-256
+pub fn hash_file(path: &Path) -> Result<String> {
+    let content = std::fs::read(path)
+        .map_err(|e| anyhow::anyhow!("无法读取文件 {}: {}", path.display(), e))?;
+    Ok(hash_bytes(&content))
+}
+
+pub fn hash_bytes(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 影响输出内容的全部设置的哈希：有效配置（不含预设表）、模板内容、试听选项和工具版本
+pub fn config_hash(config: &Config, options: &ConvertOptions) -> Result<String> {
+    let mut effective = serde_json::to_value(config)?;
+    if let Some(object) = effective.as_object_mut() {
+        object.remove("presets");
+    }
+    let template = config
+        .output_settings
+        .template
+        .as_deref()
+        .and_then(|path| template::load(path).ok());
+    let fingerprint = serde_json::json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "config": effective,
+        "template": template,
+        "preview": options.preview,
+    });
+    Ok(hash_bytes(fingerprint.to_string().as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn job(root: &TempDir) -> ConvertJob {
+        ConvertJob {
+            input: root.path().join("boom.wav"),
+            output: root.path().join("out/boom.rs"),
+        }
+    }
+
+    #[test]
+    fn test_status_transitions() {
+        let root = TempDir::new().unwrap();
+        let job = job(&root);
+        let out_dir = root.path().join("out");
+        std::fs::create_dir_all(&out_dir).unwrap();
+        std::fs::write(&job.output, "const A: [f32; 0] = [];").unwrap();
+
+        let mut cache = Cache::load(&out_dir);
+        assert_eq!(cache.status(&job, "in", "cfg").unwrap(), CacheStatus::Stale);
+
+        cache.record(&job, "in", "cfg").unwrap();
+        cache.save().unwrap();
+        let cache = Cache::load(&out_dir);
+        assert_eq!(cache.entry(&job).unwrap().input, job.input.to_string_lossy());
+        assert_eq!(cache.status(&job, "in", "cfg").unwrap(), CacheStatus::Fresh);
+        assert_eq!(cache.status(&job, "in2", "cfg").unwrap(), CacheStatus::Stale);
+        assert_eq!(cache.status(&job, "in", "cfg2").unwrap(), CacheStatus::Stale);
+
+        std::fs::write(&job.output, "// edited").unwrap();
+        assert_eq!(cache.status(&job, "in", "cfg").unwrap(), CacheStatus::OutputModified);
+
+        std::fs::remove_file(&job.output).unwrap();
+        assert_eq!(cache.status(&job, "in", "cfg").unwrap(), CacheStatus::OutputMissing);
+    }
+
+    #[test]
+    fn test_invalid_manifest_is_ignored() {
+        let root = TempDir::new().unwrap();
+        std::fs::write(root.path().join(MANIFEST_NAME), "{ not json").unwrap();
+        let cache = Cache::load(root.path());
+        assert!(cache.manifest.entries.is_empty());
+    }
+
+    #[test]
+    fn test_config_hash() {
+        let options = ConvertOptions::default();
+        let base = config_hash(&Config::default(), &options).unwrap();
+        assert_eq!(base, config_hash(&Config::default(), &options).unwrap());
+        assert_eq!(
+            hash_bytes(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        let config = Config {
+            gain: 1.0,
+            ..Default::default()
+        };
+        assert_ne!(base, config_hash(&config, &options).unwrap());
+
+        // 预设表不影响输出
+        let mut config = Config::default();
+        config.presets.insert("mine".to_string(), Default::default());
+        assert_eq!(base, config_hash(&config, &options).unwrap());
+
+        let preview = ConvertOptions {
+            preview: true,
+            ..Default::default()
+        };
+        assert_ne!(base, config_hash(&Config::default(), &preview).unwrap());
+    }
+}
@@ -0,0 +1,412 @@
+//! 转换命令的执行流程：单个文件的解码 → 处理 → 输出，以及目录输入时的任务列表
+
+use crate::audio::converter::{AudioConverter, ConvertedAudio};
+use crate::audio::decoder::{AudioDecoder, DecodeOptions, NativeSamples};
+use crate::audio::probe::probe_file;
+use crate::audio::features::extract as extract_features;
+use crate::cli::cache::{self, Cache, CacheStatus};
+use crate::cli::args::WavFormat;
+use crate::config::settings::Config;
+use crate::output::{array_writer::ArrayWriter, audio_writer::AudioWriter, data_writer::DataWriter};
+use crate::output::sample_format::Sample;
+use anyhow::Result;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 目录输入时作为音频文件处理的扩展名
+pub const AUDIO_EXTENSIONS: [&str; 6] = ["mp3", "wav", "flac", "ogg", "aac", "m4a"];
+/// 目录输入时输出文件的默认扩展名
+pub const DEFAULT_OUTPUT_EXTENSION: &str = "rs";
+
+/// 与配置无关的转换选项
+#[derive(Debug, Clone, Default)]
+pub struct ConvertOptions {
+    /// 在输出文件旁写出试听用的 WAV
+    pub preview: bool,
+    pub verbose: bool,
+    /// 增量缓存清单所在的目录，`None` 时总是转换
+    pub cache_dir: Option<PathBuf>,
+    /// 忽略缓存，并覆盖被手动修改的输出
+    pub force: bool,
+}
+
+/// 一批任务的执行结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchSummary {
+    pub converted: usize,
+    /// 因输入和配置未变化而跳过的文件数
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// 一个输入文件及其输出路径
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConvertJob {
+    pub input: PathBuf,
+    pub output: PathBuf,
+}
+
+/// 输入输出的根路径：单个文件，或目录到目录的映射
+#[derive(Debug, Clone)]
+pub struct JobSource {
+    pub input: PathBuf,
+    pub output: PathBuf,
+    /// 目录输入时输出文件的扩展名
+    pub extension: String,
+}
+
+impl JobSource {
+    pub fn new(input: &str, output: &str, extension: Option<&str>) -> Self {
+        Self {
+            input: PathBuf::from(input),
+            output: PathBuf::from(output),
+            extension: extension.unwrap_or(DEFAULT_OUTPUT_EXTENSION).trim_start_matches('.').to_string(),
+        }
+    }
+
+    pub fn is_directory(&self) -> bool {
+        self.input.is_dir()
+    }
+
+    /// 列出所有任务；目录输入时递归查找音频文件，输出保持相对路径并替换扩展名
+    pub fn jobs(&self) -> Result<Vec<ConvertJob>> {
+        if !self.is_directory() {
+            return Ok(vec![ConvertJob {
+                input: self.input.clone(),
+                output: self.output.clone(),
+            }]);
+        }
+        if self.output.is_file() {
+            anyhow::bail!("输入为目录时输出也必须是目录: {}", self.output.display());
+        }
+
+        let mut inputs = Vec::new();
+        collect_audio_files(&self.input, &mut inputs)?;
+        inputs.sort();
+        Ok(inputs.into_iter().filter_map(|input| self.job_for(&input)).collect())
+    }
+
+    /// 文件对应的任务，不属于该输入（或不是音频文件）时返回 `None`
+    pub fn job_for(&self, path: &Path) -> Option<ConvertJob> {
+        if !self.is_directory() {
+            return same_file(path, &self.input).then(|| ConvertJob {
+                input: self.input.clone(),
+                output: self.output.clone(),
+            });
+        }
+        if !is_audio_file(path) {
+            return None;
+        }
+        // 文件监视报告的是绝对路径，输入目录可能是相对路径
+        let relative = match path.strip_prefix(&self.input) {
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => path.strip_prefix(self.input.canonicalize().ok()?).ok()?.to_path_buf(),
+        };
+        Some(ConvertJob {
+            input: self.input.join(&relative),
+            output: self.output.join(&relative).with_extension(&self.extension),
+        })
+    }
+}
+
+/// 按扩展名判断是否为支持的音频文件
+pub fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| AUDIO_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+fn collect_audio_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| anyhow::anyhow!("无法读取目录 {}: {}", dir.display(), e))?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_audio_files(&path, files)?;
+        } else if is_audio_file(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// 路径相同，或规范化后指向同一个文件
+pub(crate) fn same_file(a: &Path, b: &Path) -> bool {
+    a == b || matches!((a.canonicalize(), b.canonicalize()), (Ok(a), Ok(b)) if a == b)
+}
+
+/// 按 `--jobs` 初始化全局线程池，`None` 时使用全部 CPU 核心
+///
+/// 多个文件的并发转换和单个文件内的分块处理共用该线程池，因此 `--jobs 1` 等同于完全顺序执行。
+pub fn init_thread_pool(jobs: Option<usize>) -> Result<()> {
+    if jobs == Some(0) {
+        anyhow::bail!("--jobs 必须大于 0");
+    }
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs.unwrap_or(0))
+        .build_global()
+        .map_err(|e| anyhow::anyhow!("无法创建线程池: {}", e))
+}
+
+/// 转换一批文件，单个文件失败时报告错误并继续；设置了缓存目录时跳过未变化的文件
+pub fn convert_all(jobs: &[ConvertJob], config: &Config, options: &ConvertOptions) -> BatchSummary {
+    let tasks: Vec<(&ConvertJob, &Config)> = jobs.iter().map(|job| (job, config)).collect();
+    convert_tasks(&tasks, options)
+}
+
+/// 在线程池中并发执行各自带有配置的任务
+///
+/// 错误在全部任务结束后按任务顺序报告。
+pub fn convert_tasks(tasks: &[(&ConvertJob, &Config)], options: &ConvertOptions) -> BatchSummary {
+    let cache = options.cache_dir.as_deref().map(|dir| Mutex::new(Cache::load(dir)));
+    let run = |&(job, config): &(&ConvertJob, &Config)| match &cache {
+        Some(cache) => convert_cached(job, config, options, cache),
+        None => convert_file(job, config, options).map(|_| true),
+    };
+    let results: Vec<Result<bool>> = tasks.par_iter().map(run).collect();
+
+    let mut summary = BatchSummary::default();
+    for ((job, _), result) in tasks.iter().zip(results) {
+        match result {
+            Ok(true) => summary.converted += 1,
+            Ok(false) => summary.skipped += 1,
+            Err(e) => {
+                eprintln!("转换失败: {}: {:#}", job.input.display(), e);
+                summary.failed += 1;
+            }
+        }
+    }
+
+    if let Some(cache) = cache {
+        if let Err(e) = cache.into_inner().map_err(|_| anyhow::anyhow!("缓存清单已损坏")).and_then(|c| c.save()) {
+            eprintln!("警告: {:#}", e);
+        }
+    }
+    summary
+}
+
+/// 按缓存状态决定是否转换，返回 `false` 表示跳过；转换期间不持有缓存锁
+fn convert_cached(job: &ConvertJob, config: &Config, options: &ConvertOptions, cache: &Mutex<Cache>) -> Result<bool> {
+    let lock = || cache.lock().map_err(|_| anyhow::anyhow!("缓存清单已损坏"));
+    let input_hash = cache::hash_file(&job.input)?;
+    let config_hash = cache::config_hash(config, options)?;
+    let status = lock()?.status(job, &input_hash, &config_hash)?;
+    match status {
+        CacheStatus::Fresh if !options.force => {
+            if options.verbose {
+                println!("未变化，跳过: {}", job.input.display());
+            }
+            return Ok(false);
+        }
+        CacheStatus::OutputModified if !options.force => anyhow::bail!(
+            "输出文件 {} 在上次生成后被修改，未覆盖（使用 --force 重新生成）",
+            job.output.display()
+        ),
+        CacheStatus::OutputMissing => println!("输出文件已被删除，重新生成: {}", job.output.display()),
+        _ => {}
+    }
+
+    convert_file(job, config, options)?;
+    lock()?.record(job, &input_hash, &config_hash)?;
+    Ok(true)
+}
+
+/// 解码、处理并按输出扩展名写出一个文件
+pub fn convert_file(job: &ConvertJob, config: &Config, options: &ConvertOptions) -> Result<()> {
+    let input_path = job.input.to_string_lossy();
+    let output_path = job.output.to_string_lossy();
+
+    let converter = AudioConverter::new(config.clone());
+    if options.verbose {
+        println!("处理链: {}", converter.stage_names().join(" → "));
+    }
+
+    // 处理链用不到的声道在解码时丢弃
+    let max_channels = converter.max_input_channels();
+    // 输出为整数音频、整数数组或整数数据文件且处理链不改动该文件时，整数 PCM 按源位宽解码并直接写出
+    let wav_format = wav_format(config);
+    let integer_output = if AudioWriter::is_audio_path(&output_path) {
+        wav_format != WavFormat::Float32
+    } else {
+        config.output_format.bits().is_some()
+    };
+    let native = integer_output
+        && config.features.is_none()
+        && is_identity_for(&input_path, &converter, max_channels);
+
+    // 解码音频文件
+    println!("正在解码音频文件: {}", input_path);
+    let mut decoder = AudioDecoder::new();
+    decoder.decode_file_with(&input_path, &DecodeOptions { native, max_channels, quiet: false })?;
+    let (sample_rate, channels) = (decoder.get_sample_rate(), decoder.get_channels());
+
+    if let Some(native) = decoder.take_native_samples() {
+        println!("解码完成: 采样率={}Hz, 声道数={}, 样本数={}, {} 位整数直通",
+                 sample_rate,
+                 channels,
+                 native.len(),
+                 native.bits());
+        let format = config.output_format;
+        return match native {
+            NativeSamples::I8(samples) => {
+                write_samples(&ConvertedAudio { samples, sample_rate, channels, format }, job, config, options)
+            }
+            NativeSamples::I16(samples) => {
+                write_samples(&ConvertedAudio { samples, sample_rate, channels, format }, job, config, options)
+            }
+            NativeSamples::I32(samples) => {
+                write_samples(&ConvertedAudio { samples, sample_rate, channels, format }, job, config, options)
+            }
+        };
+    }
+
+    println!("解码完成: 采样率={}Hz, 声道数={}, 样本数={}",
+             sample_rate,
+             channels,
+             decoder.get_samples().len());
+
+    // 转换音频
+    let converted = converter.convert(decoder.get_samples(), sample_rate, channels)?;
+    let output_settings = &config.output_settings;
+
+    // 启用特征提取时输出特征矩阵，其余输出与整数直通相同
+    if let Some(feature_settings) = &config.features {
+        report_converted(&converted, job)?;
+        let features = extract_features(
+            &converted.samples,
+            converted.sample_rate,
+            converted.channels,
+            feature_settings,
+        )?;
+        println!("特征提取完成: 类型={}, 帧数={}, 维数={}",
+                 feature_settings.kind.name(),
+                 features.frames,
+                 features.bins);
+
+        if AudioWriter::is_audio_path(&output_path) {
+            anyhow::bail!("特征矩阵不能写为音频文件: {}", output_path);
+        } else if DataWriter::is_data_path(&output_path) {
+            DataWriter::write_features(&features, config.output_format, &output_path, output_settings)?;
+        } else {
+            ArrayWriter::write_features(&features, config.output_format, &output_path, output_settings)?;
+        }
+        write_preview(&converted, job, config, options)
+    } else {
+        write_samples(&converted, job, config, options)
+    }
+}
+
+/// 未显式指定时按数组格式选择音频输出格式
+fn wav_format(config: &Config) -> WavFormat {
+    config
+        .output_settings
+        .wav_format
+        .unwrap_or_else(|| AudioWriter::default_format(config.output_format))
+}
+
+/// 探测文件的采样率和声道数，判断处理链是否不改动解码结果
+fn is_identity_for(path: &str, converter: &AudioConverter, max_channels: Option<u32>) -> bool {
+    let Ok(info) = probe_file(path) else {
+        return false;
+    };
+    let track = info.tracks.iter().find(|t| Some(t.id) == info.default_track);
+    match track.and_then(|t| Some((t.sample_rate?, t.channels?))) {
+        Some((sample_rate, channels)) => {
+            converter.is_identity(sample_rate, max_channels.map_or(channels, |max| channels.min(max)))
+        }
+        None => false,
+    }
+}
+
+/// 报告转换结果，并创建输出文件所在的目录
+fn report_converted<S: Sample>(converted: &ConvertedAudio<S>, job: &ConvertJob) -> Result<()> {
+    println!("转换完成: 采样率={}Hz, 声道数={}, 样本数={}",
+             converted.sample_rate,
+             converted.channels,
+             converted.samples.len());
+
+    // 目录输入时输出可能位于尚不存在的子目录中
+    if let Some(parent) = job.output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| anyhow::anyhow!("无法创建输出目录 {}: {}", parent.display(), e))?;
+    }
+    Ok(())
+}
+
+/// 按扩展名写出音频文件、数据文件或数组，整数直通的样本不经过浮点
+fn write_samples<S: Sample>(
+    converted: &ConvertedAudio<S>,
+    job: &ConvertJob,
+    config: &Config,
+    options: &ConvertOptions,
+) -> Result<()> {
+    report_converted(converted, job)?;
+    let output_path = job.output.to_string_lossy();
+    if AudioWriter::is_audio_path(&output_path) {
+        AudioWriter::write_to_file(converted, &output_path, wav_format(config))?;
+    } else if DataWriter::is_data_path(&output_path) {
+        DataWriter::write_to_file(converted, &output_path, &config.output_settings)?;
+    } else {
+        ArrayWriter::write_with_settings(converted, &output_path, &config.output_settings)?;
+    }
+    write_preview(converted, job, config, options)
+}
+
+/// 在输出文件旁写出试听用的 WAV
+fn write_preview<S: Sample>(
+    converted: &ConvertedAudio<S>,
+    job: &ConvertJob,
+    config: &Config,
+    options: &ConvertOptions,
+) -> Result<()> {
+    if options.preview && !AudioWriter::is_audio_path(&job.output.to_string_lossy()) {
+        let preview_path = job.output.with_extension("wav");
+        AudioWriter::write_wav(converted, &preview_path.to_string_lossy(), wav_format(config))?;
+        println!("试听文件已写入: {}", preview_path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_directory_jobs() {
+        let root = TempDir::new().unwrap();
+        let input = root.path().join("sounds");
+        std::fs::create_dir_all(input.join("ui")).unwrap();
+        for name in ["boom.wav", "ui/click.MP3", "notes.txt"] {
+            std::fs::write(input.join(name), b"").unwrap();
+        }
+
+        let source = JobSource::new(
+            input.to_str().unwrap(),
+            root.path().join("out").to_str().unwrap(),
+            Some(".h"),
+        );
+        let jobs = source.jobs().unwrap();
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].output, root.path().join("out/boom.h"));
+        assert_eq!(jobs[1].input, input.join("ui/click.MP3"));
+        assert_eq!(jobs[1].output, root.path().join("out/ui/click.h"));
+
+        assert_eq!(source.job_for(&input.join("ui/click.MP3")), Some(jobs[1].clone()));
+        assert!(source.job_for(&input.join("notes.txt")).is_none());
+        assert!(source.job_for(&root.path().join("other.wav")).is_none());
+    }
+
+    #[test]
+    fn test_single_file_job() {
+        let root = TempDir::new().unwrap();
+        let input = root.path().join("beep.wav");
+        std::fs::write(&input, b"").unwrap();
+
+        let source = JobSource::new(input.to_str().unwrap(), "beep.rs", None);
+        assert_eq!(source.jobs().unwrap().len(), 1);
+        assert!(source.job_for(&input).is_some());
+        assert!(source.job_for(&root.path().join("boop.wav")).is_none());
+    }
+}
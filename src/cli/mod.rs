@@ -1 +1,5 @@
 pub mod args;
+pub mod build;
+pub mod cache;
+pub mod convert;
+pub mod watch;
@@ -0,0 +1,175 @@
+//! `--watch` 模式：监视输入文件/目录和配置文件，只重新转换发生变化的文件
+//!
+//! 编辑器保存文件时通常会先写临时文件再重命名，因此单个输入文件也通过监视其所在目录实现；
+//! 短时间内的多次保存由防抖合并为一次转换。转换或配置出错时只报告错误，继续监视。
+
+use crate::cli::convert::{self, BatchSummary, ConvertJob, ConvertOptions, JobSource};
+use crate::config::settings::Config;
+use notify_debouncer_mini::{new_debouncer, notify::RecursiveMode};
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// 防抖时间：该时间内的连续变化合并为一次
+pub const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// 一批文件变化对应的操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchAction {
+    /// 配置文件有变化：重新加载配置并转换所有文件
+    ReloadConfig,
+    /// 只转换这些文件
+    Convert(Vec<ConvertJob>),
+}
+
+/// 根据变化的路径决定要执行的操作，没有相关变化时返回 `None`
+///
+/// 已删除的输入文件和位于输出目录中的文件（输出目录在输入目录内时）会被忽略。
+pub fn classify(paths: &[PathBuf], source: &JobSource, config_files: &[PathBuf]) -> Option<WatchAction> {
+    if paths
+        .iter()
+        .any(|path| config_files.iter().any(|config| convert::same_file(path, config)))
+    {
+        return Some(WatchAction::ReloadConfig);
+    }
+
+    let output_root = source.output.canonicalize().ok();
+    let mut jobs: Vec<ConvertJob> = Vec::new();
+    for path in paths {
+        if !path.is_file() || output_root.as_deref().is_some_and(|root| source.is_directory() && path.starts_with(root)) {
+            continue;
+        }
+        if let Some(job) = source.job_for(path) {
+            if !jobs.contains(&job) {
+                jobs.push(job);
+            }
+        }
+    }
+    (!jobs.is_empty()).then_some(WatchAction::Convert(jobs))
+}
+
+/// 先转换全部文件，然后持续监视变化；`load_config` 在配置文件变化时重新解析配置
+///
+/// 只有首次加载配置或建立监视失败时返回错误。
+pub fn watch<F>(source: &JobSource, config_files: &[PathBuf], load_config: F, options: &ConvertOptions) -> Result<()>
+where
+    F: Fn() -> Result<Config>,
+{
+    let mut config = load_config()?;
+    run(source, &config, options);
+
+    let (tx, rx) = mpsc::channel();
+    let mut debouncer = new_debouncer(DEBOUNCE, tx)?;
+    let watcher = debouncer.watcher();
+    if source.is_directory() {
+        watcher.watch(&source.input, RecursiveMode::Recursive)?;
+    } else {
+        watcher.watch(&watch_dir(&source.input)?, RecursiveMode::NonRecursive)?;
+    }
+    for file in config_files {
+        watcher.watch(&watch_dir(file)?, RecursiveMode::NonRecursive)?;
+    }
+    println!("正在监视 {} 的变化（按 Ctrl+C 退出）", source.input.display());
+
+    for result in rx {
+        let events = match result {
+            Ok(events) => events,
+            Err(e) => {
+                eprintln!("监视出错: {}", e);
+                continue;
+            }
+        };
+        let paths: Vec<PathBuf> = events.into_iter().map(|event| event.path).collect();
+        match classify(&paths, source, config_files) {
+            Some(WatchAction::ReloadConfig) => match load_config() {
+                Ok(new_config) => {
+                    println!("配置文件已变化，重新转换所有文件");
+                    config = new_config;
+                    run(source, &config, options);
+                }
+                Err(e) => eprintln!("配置无效，继续使用之前的配置: {:#}", e),
+            },
+            Some(WatchAction::Convert(jobs)) => {
+                report(convert::convert_all(&jobs, &config, options));
+            }
+            None => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// 转换全部文件，列出任务失败时只报告错误
+fn run(source: &JobSource, config: &Config, options: &ConvertOptions) {
+    match source.jobs() {
+        Ok(jobs) => report(convert::convert_all(&jobs, config, options)),
+        Err(e) => eprintln!("错误: {:#}", e),
+    }
+}
+
+fn report(summary: BatchSummary) {
+    println!(
+        "已转换 {} 个文件，跳过 {} 个，失败 {} 个，等待变化...",
+        summary.converted, summary.skipped, summary.failed
+    );
+}
+
+/// 文件所在的目录（规范化为绝对路径，与监视事件中的路径一致）
+fn watch_dir(file: &Path) -> Result<PathBuf> {
+    let file = file
+        .canonicalize()
+        .map_err(|e| anyhow::anyhow!("无法监视 {}: {}", file.display(), e))?;
+    Ok(file.parent().map(Path::to_path_buf).unwrap_or(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, JobSource, PathBuf) {
+        let root = TempDir::new().unwrap();
+        let input = root.path().join("sounds");
+        std::fs::create_dir_all(&input).unwrap();
+        for name in ["a.wav", "b.wav", "readme.txt"] {
+            std::fs::write(input.join(name), b"").unwrap();
+        }
+        let config = root.path().join("audio.toml");
+        std::fs::write(&config, "").unwrap();
+
+        let source = JobSource::new(input.to_str().unwrap(), input.join("out").to_str().unwrap(), None);
+        (root, source, config)
+    }
+
+    #[test]
+    fn test_classify_changed_inputs() {
+        let (root, source, config) = setup();
+        let input = root.path().join("sounds");
+        let config_files = [config];
+
+        // 同一个文件的多次事件只转换一次，非音频文件被忽略
+        let paths = vec![input.join("a.wav"), input.join("readme.txt"), input.join("a.wav")];
+        let action = classify(&paths, &source, &config_files).unwrap();
+        assert_eq!(
+            action,
+            WatchAction::Convert(vec![ConvertJob {
+                input: input.join("a.wav"),
+                output: input.join("out/a.rs"),
+            }])
+        );
+
+        // 已删除的文件和输出目录中的文件不触发转换
+        std::fs::create_dir_all(input.join("out")).unwrap();
+        std::fs::write(input.join("out/c.wav"), b"").unwrap();
+        let paths = vec![input.join("gone.wav"), input.join("out/c.wav")];
+        assert_eq!(classify(&paths, &source, &config_files), None);
+    }
+
+    #[test]
+    fn test_classify_config_change() {
+        let (root, source, config) = setup();
+        let paths = vec![root.path().join("sounds/b.wav"), config.canonicalize().unwrap()];
+        assert_eq!(classify(&paths, &source, &[config]), Some(WatchAction::ReloadConfig));
+    }
+}
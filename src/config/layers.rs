@@ -0,0 +1,388 @@
+//! 分层配置解析
+//!
+//! 按优先级从低到高合并：
+//!
+//! 1. 内置默认值
+//! 2. 用户配置：`$XDG_CONFIG_HOME/audio-converter/config.{toml,yaml,yml,json}`
+//! 3. 项目配置：从工作目录向上查找的第一个 `audio-converter.toml`
+//! 4. `-C` 指定的配置文件
+//! 5. 环境变量 `AUDIO_CONVERTER_*`，嵌套字段用 `__` 分隔，例如
+//!    `AUDIO_CONVERTER_OUTPUT_SETTINGS__LAYOUT=planar`，值按目标字段的类型解释
+//! 6. 预设和其他命令行参数（见 [`Config::merge_with_args`]）
+//!
+//! 各层先转换为 JSON 值逐字段合并（对象递归合并，数组整体替换），
+//! 最后再反序列化为 [`Config`]，同时记录每个字段来自哪一层。
+
+use crate::cli::args::Args;
+use crate::config::settings::{Config, ConfigFormat};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+
+/// 项目配置文件名
+pub const PROJECT_CONFIG_NAME: &str = "audio-converter.toml";
+/// 环境变量前缀
+pub const ENV_PREFIX: &str = "AUDIO_CONVERTER_";
+/// 用户配置目录下的文件名，按顺序取第一个存在的文件
+const USER_CONFIG_NAMES: [&str; 4] = ["config.toml", "config.yaml", "config.yml", "config.json"];
+
+const DEFAULT_SOURCE: &str = "默认值";
+
+/// 配置来源的查找位置，默认取自当前进程环境
+#[derive(Debug, Clone, Default)]
+pub struct LayerOptions {
+    /// `-C` 指定的配置文件
+    pub config_file: Option<String>,
+    /// 项目配置的查找起点
+    pub working_dir: Option<PathBuf>,
+    /// 用户配置目录（`audio-converter` 子目录的上一级）
+    pub user_config_dir: Option<PathBuf>,
+    /// `AUDIO_CONVERTER_*` 环境变量
+    pub env: Vec<(String, String)>,
+}
+
+impl LayerOptions {
+    /// 从当前进程的工作目录、XDG 配置目录和环境变量构造
+    pub fn from_env(config_file: Option<String>) -> Self {
+        Self {
+            config_file,
+            working_dir: std::env::current_dir().ok(),
+            user_config_dir: dirs::config_dir(),
+            env: std::env::vars().filter(|(key, _)| key.starts_with(ENV_PREFIX)).collect(),
+        }
+    }
+
+    /// 按合并顺序列出存在的配置文件及其层名称（用户配置、项目配置、`-C` 配置文件）
+    pub fn config_files(&self) -> Vec<(&'static str, PathBuf)> {
+        let mut files = Vec::new();
+        if let Some(path) = self.user_config_dir.as_deref().and_then(find_user_config) {
+            files.push(("用户配置", path));
+        }
+        if let Some(path) = self.working_dir.as_deref().and_then(find_project_config) {
+            files.push(("项目配置", path));
+        }
+        if let Some(path) = &self.config_file {
+            files.push(("配置文件", PathBuf::from(path)));
+        }
+        files
+    }
+}
+
+/// 合并后的配置以及每个字段的来源（键为 `output_settings.layout` 形式的路径）
+#[derive(Debug)]
+pub struct LayeredConfig {
+    pub config: Config,
+    pub sources: BTreeMap<String, String>,
+}
+
+impl LayeredConfig {
+    /// 依次合并所有配置层
+    pub fn resolve(options: &LayerOptions) -> Result<Self> {
+        let mut merged = serde_json::to_value(Config::default())?;
+        let mut sources = BTreeMap::new();
+
+        for (kind, path) in options.config_files() {
+            let path = path.to_string_lossy().into_owned();
+            let layer = read_layer(&path)?;
+            merge(&mut merged, layer, "", &format!("{} {}", kind, path), &mut sources);
+        }
+
+        for (key, raw) in &options.env {
+            let Some(name) = key.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            let path: Vec<String> = name.split("__").map(|part| part.to_lowercase()).collect();
+            let source = format!("环境变量 {}", key);
+            let value = env_value(&merged, &path, raw);
+            merge(&mut merged, nest(&path, value), "", &source, &mut sources);
+        }
+
+        let config: Config = serde_json::from_value(merged)
+            .map_err(|e| anyhow::anyhow!("配置无效（请检查 {}* 环境变量）: {}", ENV_PREFIX, e))?;
+        config.validate()?;
+
+        Ok(Self { config, sources })
+    }
+
+    /// 合并命令行参数（含 `--preset`），并把改动的字段记为对应来源
+    pub fn merge_with_args(&mut self, args: &Args) -> Result<()> {
+        if let Some(name) = &args.preset {
+            self.apply_preset(name)?;
+        }
+
+        let before = flatten(&serde_json::to_value(&self.config)?);
+        self.config.apply_flags(args)?;
+        self.record_changes(&before, "命令行")?;
+
+        self.config.validate()
+    }
+
+    /// 应用预设，预设中设置的字段记为该预设
+    pub fn apply_preset(&mut self, name: &str) -> Result<()> {
+        let before = flatten(&serde_json::to_value(&self.config)?);
+        self.config.apply_preset(name)?;
+        self.record_changes(&before, &format!("预设 {}", name))
+    }
+
+    /// 字段的来源，未被任何一层设置时为「默认值」
+    pub fn source(&self, key: &str) -> &str {
+        self.sources.get(key).map(|s| s.as_str()).unwrap_or(DEFAULT_SOURCE)
+    }
+
+    /// 每行一个字段：`键 = 值  # 来源`
+    pub fn describe(&self) -> Result<String> {
+        let values = flatten(&serde_json::to_value(&self.config)?);
+        let width = values.keys().map(|k| k.len()).max().unwrap_or(0);
+
+        let mut output = String::new();
+        for (key, value) in &values {
+            output.push_str(&format!("{:<width$} = {}  # {}\n", key, value, self.source(key), width = width));
+        }
+        Ok(output)
+    }
+
+    /// JSON 形式：`{ config, sources }`，sources 覆盖所有字段
+    pub fn to_json(&self) -> Result<Value> {
+        let values = flatten(&serde_json::to_value(&self.config)?);
+        let sources: BTreeMap<&str, &str> = values.keys().map(|k| (k.as_str(), self.source(k))).collect();
+        Ok(serde_json::json!({
+            "config": self.config,
+            "sources": sources
+        }))
+    }
+
+    fn record_changes(&mut self, before: &BTreeMap<String, Value>, source: &str) -> Result<()> {
+        let after = flatten(&serde_json::to_value(&self.config)?);
+        for (key, value) in after {
+            if before.get(&key) != Some(&value) {
+                self.sources.insert(key, source.to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 按目标字段的类型解释环境变量的值：能按 JSON 解析且目标字段接受时按 JSON
+/// 处理（数字、布尔、null、数组），否则作为字符串，因此字符串字段无需加引号
+fn env_value(merged: &Value, path: &[String], raw: &str) -> Value {
+    let string = Value::String(raw.to_string());
+    let Ok(parsed) = serde_json::from_str::<Value>(raw) else {
+        return string;
+    };
+    if parsed.is_string() {
+        return parsed;
+    }
+
+    let accepts = |value: &Value| {
+        let mut trial = merged.clone();
+        merge_values(&mut trial, nest(path, value.clone()));
+        serde_json::from_value::<Config>(trial).is_ok()
+    };
+    if !accepts(&parsed) && accepts(&string) {
+        string
+    } else {
+        parsed
+    }
+}
+
+/// 把值包装为 `path` 指定的嵌套对象
+fn nest(path: &[String], value: Value) -> Value {
+    path.iter().rev().fold(value, |inner, part| {
+        let mut object = Map::new();
+        object.insert(part.clone(), inner);
+        Value::Object(object)
+    })
+}
+
+/// 读取一层配置文件：先按完整配置解析以得到带文件名的错误信息，再转换为 JSON 值
+fn read_layer(path: &str) -> Result<Value> {
+    Config::from_file(path)?;
+
+    let content = std::fs::read_to_string(path)?;
+    let value = match ConfigFormat::from_path(path) {
+        ConfigFormat::Json => serde_json::from_str(&content)?,
+        ConfigFormat::Toml => toml::from_str(&content)?,
+        ConfigFormat::Yaml => serde_yaml::from_str(&content)?,
+    };
+    Ok(value)
+}
+
+fn find_user_config(config_dir: &Path) -> Option<PathBuf> {
+    let dir = config_dir.join("audio-converter");
+    USER_CONFIG_NAMES.iter().map(|name| dir.join(name)).find(|path| path.is_file())
+}
+
+/// 从 `start` 开始逐级向上查找项目配置
+pub fn find_project_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_CONFIG_NAME))
+        .find(|path| path.is_file())
+}
+
+/// 把 `layer` 中设置的字段合并进 `base`：对象递归合并，数组和标量整体替换
+pub fn merge_values(base: &mut Value, layer: Value) {
+    merge(base, layer, "", "", &mut BTreeMap::new());
+}
+
+/// 把 `layer` 合并进 `base`，记录被设置的叶子字段的来源
+fn merge(base: &mut Value, layer: Value, prefix: &str, source: &str, sources: &mut BTreeMap<String, String>) {
+    match (base, layer) {
+        (Value::Object(base), Value::Object(layer)) => {
+            for (key, value) in layer {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                let slot = base.entry(key).or_insert(Value::Null);
+                merge(slot, value, &path, source, sources);
+            }
+        }
+        (base, layer) => {
+            // 整体替换时，原有的子字段来源一并失效
+            let nested = format!("{}.", prefix);
+            sources.retain(|key, _| !key.starts_with(&nested));
+            for key in flatten_with_prefix(&layer, prefix).into_keys() {
+                sources.insert(key, source.to_string());
+            }
+            *base = layer;
+        }
+    }
+}
+
+/// 展开为叶子字段路径 → 值；空对象本身视为叶子
+fn flatten(value: &Value) -> BTreeMap<String, Value> {
+    flatten_with_prefix(value, "")
+}
+
+fn flatten_with_prefix(value: &Value, prefix: &str) -> BTreeMap<String, Value> {
+    let mut output = BTreeMap::new();
+    match value {
+        Value::Object(object) if !object.is_empty() => {
+            for (key, value) in object {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                output.extend(flatten_with_prefix(value, &path));
+            }
+        }
+        value => {
+            output.insert(prefix.to_string(), value.clone());
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::args::{ArrayLayout, OutputFormat};
+    use clap::Parser;
+    use tempfile::TempDir;
+
+    fn options(root: &TempDir) -> LayerOptions {
+        LayerOptions {
+            config_file: None,
+            working_dir: Some(root.path().join("project/sub/dir")),
+            user_config_dir: Some(root.path().join("xdg")),
+            env: Vec::new(),
+        }
+    }
+
+    fn write(root: &TempDir, path: &str, content: &str) {
+        let path = root.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_layer_precedence() {
+        let root = TempDir::new().unwrap();
+        write(&root, "project/sub/dir/.keep", "");
+        write(&root, "xdg/audio-converter/config.yaml", "output_format: i16\nsample_rate: 22050\ngain: 1.0\n");
+        write(&root, "project/audio-converter.toml", "sample_rate = 16000\n[output_settings]\nlayout = \"planar\"\n");
+
+        let mut options = options(&root);
+        options.env = vec![
+            ("AUDIO_CONVERTER_GAIN".to_string(), "-3".to_string()),
+            ("AUDIO_CONVERTER_OUTPUT_SETTINGS__PRECISION".to_string(), "3".to_string()),
+        ];
+        let layered = LayeredConfig::resolve(&options).unwrap();
+
+        assert_eq!(layered.config.output_format, OutputFormat::I16);
+        assert_eq!(layered.config.sample_rate, Some(16000));
+        assert_eq!(layered.config.gain, -3.0);
+        assert_eq!(layered.config.output_settings.layout, ArrayLayout::Planar);
+        assert_eq!(layered.config.output_settings.precision, 3);
+
+        assert!(layered.source("output_format").starts_with("用户配置"));
+        assert!(layered.source("sample_rate").starts_with("项目配置"));
+        assert!(layered.source("output_settings.layout").ends_with("audio-converter.toml"));
+        assert_eq!(layered.source("gain"), "环境变量 AUDIO_CONVERTER_GAIN");
+        assert_eq!(layered.source("normalize"), "默认值");
+    }
+
+    #[test]
+    fn test_explicit_file_and_errors() {
+        let root = TempDir::new().unwrap();
+        write(&root, "project/audio-converter.toml", "channels = 2\n");
+        write(&root, "explicit.json", r#"{ "channels": 1 }"#);
+
+        let mut options = options(&root);
+        options.config_file = Some(root.path().join("explicit.json").to_string_lossy().into_owned());
+        let layered = LayeredConfig::resolve(&options).unwrap();
+        assert_eq!(layered.config.channels, Some(1));
+        assert!(layered.source("channels").starts_with("配置文件"));
+
+        options.env = vec![("AUDIO_CONVERTER_CHANNELS".to_string(), "12".to_string())];
+        assert!(LayeredConfig::resolve(&options).unwrap_err().to_string().contains("channels"));
+
+        options.env = vec![("AUDIO_CONVERTER_SAMPLE_RTE".to_string(), "1".to_string())];
+        assert!(LayeredConfig::resolve(&options).unwrap_err().to_string().contains("sample_rte"));
+    }
+
+    #[test]
+    fn test_env_values_follow_field_type() {
+        let root = TempDir::new().unwrap();
+        let mut options = options(&root);
+        options.env = vec![
+            ("AUDIO_CONVERTER_OUTPUT_SETTINGS__NAME".to_string(), "123".to_string()),
+            ("AUDIO_CONVERTER_OUTPUT_SETTINGS__TEMPLATE".to_string(), "true".to_string()),
+            ("AUDIO_CONVERTER_OUTPUT_SETTINGS__VALUES_PER_LINE".to_string(), "8".to_string()),
+            ("AUDIO_CONVERTER_NORMALIZE".to_string(), "true".to_string()),
+            ("AUDIO_CONVERTER_SAMPLE_RATE".to_string(), "null".to_string()),
+        ];
+        let layered = LayeredConfig::resolve(&options).unwrap();
+
+        assert_eq!(layered.config.output_settings.name.as_deref(), Some("123"));
+        assert_eq!(layered.config.output_settings.template.as_deref(), Some("true"));
+        assert_eq!(layered.config.output_settings.values_per_line, 8);
+        assert!(layered.config.normalize);
+        assert_eq!(layered.config.sample_rate, None);
+
+        // 数值字段收到非数值时仍报告该字段
+        options.env = vec![("AUDIO_CONVERTER_GAIN".to_string(), "loud".to_string())];
+        assert!(LayeredConfig::resolve(&options).unwrap_err().to_string().contains("AUDIO_CONVERTER_"));
+    }
+
+    #[test]
+    fn test_preset_then_flags() {
+        let root = TempDir::new().unwrap();
+        let mut layered = LayeredConfig::resolve(&options(&root)).unwrap();
+        let args = Args::try_parse_from(["audio-converter", "--preset", "gba-8bit", "-s", "22050"]).unwrap();
+        layered.merge_with_args(&args).unwrap();
+
+        assert_eq!(layered.config.output_format, OutputFormat::I8);
+        assert_eq!(layered.config.sample_rate, Some(22050));
+        assert_eq!(layered.source("output_format"), "预设 gba-8bit");
+        assert_eq!(layered.source("sample_rate"), "命令行");
+    }
+
+    #[test]
+    fn test_flatten_and_replace() {
+        let mut base = serde_json::json!({ "a": { "b": 1, "c": [1, 2] } });
+        let mut sources = BTreeMap::new();
+        merge(&mut base, serde_json::json!({ "a": { "c": [3] } }), "", "x", &mut sources);
+
+        assert_eq!(base, serde_json::json!({ "a": { "b": 1, "c": [3] } }));
+        assert_eq!(sources.get("a.c").map(|s| s.as_str()), Some("x"));
+        assert!(!sources.contains_key("a.b"));
+        assert_eq!(flatten(&base).len(), 2);
+    }
+}
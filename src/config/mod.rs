@@ -1 +1,3 @@
+pub mod layers;
+pub mod presets;
 pub mod settings;
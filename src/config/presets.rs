@@ -0,0 +1,135 @@
+//! 命名预设：常用目标平台的参数组合
+//!
+//! 预设只包含需要覆盖的字段。合并顺序为
+//! 默认值 → 配置文件 → 预设（`--preset`）→ 其他命令行参数，
+//! 配置文件 `presets` 表中的同名预设优先于内置预设。
+
+use crate::cli::args::{ArrayLayout, OutputFormat};
+use crate::config::settings::Config;
+use serde::{Deserialize, Serialize};
+
+/// 一组可选的配置覆盖项
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Preset {
+    /// 说明文字，仅用于显示
+    pub description: Option<String>,
+    pub output_format: Option<OutputFormat>,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u32>,
+    pub gain: Option<f32>,
+    pub normalize: Option<bool>,
+    pub layout: Option<ArrayLayout>,
+    /// 模板文件路径或 `builtin:<名称>`
+    pub template: Option<String>,
+    pub values_per_line: Option<usize>,
+}
+
+/// 内置预设名称
+pub const BUILTIN_NAMES: [&str; 4] = ["esp32-i2s-16k", "web-audio", "ml-16k-mono", "gba-8bit"];
+
+/// 按名称查找内置预设
+pub fn builtin(name: &str) -> Option<Preset> {
+    let preset = match name {
+        "esp32-i2s-16k" => Preset {
+            description: Some("ESP32 I2S：单声道 16 位 16kHz，输出 C 头文件".to_string()),
+            output_format: Some(OutputFormat::I16),
+            sample_rate: Some(16000),
+            channels: Some(1),
+            template: Some("builtin:c-header".to_string()),
+            ..Default::default()
+        },
+        "web-audio" => Preset {
+            description: Some("Web Audio：f32 44.1kHz，输出 JavaScript Float32Array".to_string()),
+            output_format: Some(OutputFormat::F32),
+            sample_rate: Some(44100),
+            template: Some("builtin:js-float32array".to_string()),
+            ..Default::default()
+        },
+        "ml-16k-mono" => Preset {
+            description: Some("机器学习输入：单声道 f32 16kHz，配合 .npy 输出使用".to_string()),
+            output_format: Some(OutputFormat::F32),
+            sample_rate: Some(16000),
+            channels: Some(1),
+            ..Default::default()
+        },
+        "gba-8bit" => Preset {
+            description: Some("GBA Direct Sound：单声道 8 位有符号 18157Hz".to_string()),
+            output_format: Some(OutputFormat::I8),
+            sample_rate: Some(18157),
+            channels: Some(1),
+            values_per_line: Some(16),
+            ..Default::default()
+        },
+        _ => return None,
+    };
+    Some(preset)
+}
+
+impl Preset {
+    /// 用预设中设置的字段覆盖配置
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(format) = self.output_format {
+            config.output_format = format;
+        }
+        if let Some(sample_rate) = self.sample_rate {
+            config.sample_rate = Some(sample_rate);
+        }
+        if let Some(channels) = self.channels {
+            config.channels = Some(channels);
+        }
+        if let Some(gain) = self.gain {
+            config.gain = gain;
+        }
+        if let Some(normalize) = self.normalize {
+            config.normalize = normalize;
+        }
+        if let Some(layout) = self.layout {
+            config.output_settings.layout = layout;
+        }
+        if let Some(template) = &self.template {
+            config.output_settings.template = Some(template.clone());
+        }
+        if let Some(per_line) = self.values_per_line {
+            config.output_settings.values_per_line = per_line;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_presets() {
+        for name in BUILTIN_NAMES {
+            assert!(builtin(name).is_some(), "{}", name);
+        }
+        assert!(builtin("unknown").is_none());
+
+        let mut config = Config::default();
+        builtin("esp32-i2s-16k").unwrap().apply(&mut config);
+        assert_eq!(config.output_format, OutputFormat::I16);
+        assert_eq!(config.sample_rate, Some(16000));
+        assert_eq!(config.channels, Some(1));
+        assert_eq!(config.output_settings.template.as_deref(), Some("builtin:c-header"));
+    }
+
+    #[test]
+    fn test_apply_only_set_fields() {
+        let mut config = Config {
+            gain: 3.0,
+            channels: Some(2),
+            ..Default::default()
+        };
+        let preset = Preset {
+            sample_rate: Some(8000),
+            ..Default::default()
+        };
+        preset.apply(&mut config);
+
+        assert_eq!(config.sample_rate, Some(8000));
+        assert_eq!(config.channels, Some(2));
+        assert_eq!(config.gain, 3.0);
+    }
+}
@@ -1,21 +1,129 @@
 use serde::{Deserialize, Serialize};
-use crate::cli::args::OutputFormat;
+use crate::audio::features::FeatureSettings;
+use crate::audio::filter::FilterSettings;
+use crate::audio::pipeline::{self, ProcessingStep};
+use crate::config::presets::{self, Preset};
+use crate::cli::args::{flag_pair, ArrayLayout, Endianness, FloatFormat, OutputFormat, WavFormat};
+use std::collections::BTreeMap;
+use std::path::Path;
 
-#[derive(Serialize, Deserialize, Debug)]
+/// 支持的最大声道数
+pub const MAX_CHANNELS: u32 = 8;
+/// 增益的允许范围 (±dB)
+pub const MAX_GAIN_DB: f32 = 60.0;
+
+/// 配置文件格式，按扩展名选择
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// `.toml` → TOML，`.yaml`/`.yml` → YAML，其他扩展名（包括无扩展名）按 JSON 解析
+    pub fn from_path(path: &str) -> Self {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase());
+        match extension.as_deref() {
+            Some("toml") => ConfigFormat::Toml,
+            Some("yaml") | Some("yml") => ConfigFormat::Yaml,
+            _ => ConfigFormat::Json,
+        }
+    }
+}
+
+/// 所有字段均可省略，未知字段会报错
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
 pub struct Config {
     pub output_format: OutputFormat,
     pub sample_rate: Option<u32>,
     pub channels: Option<u32>,
     pub gain: f32,
     pub normalize: bool,
+    /// 按顺序应用的滤波器
+    #[serde(default)]
+    pub filters: Vec<FilterSettings>,
+    /// 处理步骤的执行顺序，未设置时使用 增益 → 滤波 → 重采样 → 声道转换 → 归一化
+    #[serde(default)]
+    pub processing_chain: Option<Vec<ProcessingStep>>,
+    /// 设置后输出频谱特征矩阵而不是样本
+    #[serde(default)]
+    pub features: Option<FeatureSettings>,
     pub output_settings: OutputSettings,
+    /// 用户自定义预设，可用 `--preset` 选择，同名时覆盖内置预设
+    pub presets: BTreeMap<String, Preset>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
 pub struct OutputSettings {
+    /// 数组类型，可由 `--array-type` 覆盖；生成的数组代码目前不使用该字段
     pub array_type: String,
     pub include_metadata: bool,
+    /// 是否压缩，可由 `--compress` / `--no-compress` 覆盖；现有输出目前不使用该字段
     pub compress: bool,
+    /// 输出 WAV/FLAC 时的样本格式，未设置时按数组格式推断
+    #[serde(default)]
+    pub wav_format: Option<WavFormat>,
+    /// 数组的声道排列方式
+    #[serde(default)]
+    pub layout: ArrayLayout,
+    /// 浮点数的写法
+    #[serde(default)]
+    pub float_format: FloatFormat,
+    /// 固定小数位数写法下保留的位数
+    #[serde(default = "default_precision")]
+    pub precision: usize,
+    /// 整数使用十六进制字面量
+    #[serde(default)]
+    pub hex_integers: bool,
+    /// 每行的数值个数
+    #[serde(default = "default_values_per_line")]
+    pub values_per_line: usize,
+    /// 自定义输出模板文件，设置后替代内置的数组格式
+    #[serde(default)]
+    pub template: Option<String>,
+    /// raw PCM / NPY 输出的字节序
+    #[serde(default)]
+    pub endianness: Endianness,
+    /// CSV 输出是否包含时间戳列
+    #[serde(default)]
+    pub csv_timestamps: bool,
+    /// 生成代码中使用的标识符名称，未设置时数组常量以 AUDIO 为前缀、模板使用输出文件名
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+fn default_precision() -> usize {
+    6
+}
+
+fn default_values_per_line() -> usize {
+    1
+}
+
+impl Default for OutputSettings {
+    fn default() -> Self {
+        Self {
+            array_type: "Vec".to_string(),
+            include_metadata: true,
+            compress: false,
+            wav_format: None,
+            layout: ArrayLayout::Interleaved,
+            float_format: FloatFormat::Fixed,
+            precision: default_precision(),
+            hex_integers: false,
+            values_per_line: default_values_per_line(),
+            template: None,
+            endianness: Endianness::Little,
+            csv_timestamps: false,
+            name: None,
+        }
+    }
 }
 
 impl Default for Config {
@@ -26,40 +134,214 @@ impl Default for Config {
             channels: None,
             gain: 0.0,
             normalize: false,
-            output_settings: OutputSettings {
-                array_type: "Vec".to_string(),
-                include_metadata: true,
-                compress: false,
-            },
+            filters: Vec::new(),
+            processing_chain: None,
+            features: None,
+            output_settings: OutputSettings::default(),
+            presets: BTreeMap::new(),
         }
     }
 }
 
 impl Config {
     pub fn from_file(path: &str) -> anyhow::Result<Self> {
-        let content = std::fs::read_to_string(path)?;
-        let config: Config = serde_json::from_str(&content)?;
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("无法读取配置文件 {}: {}", path, e))?;
+        Self::parse(&content, ConfigFormat::from_path(path))
+            .map_err(|e| anyhow::anyhow!("配置文件 {} 无效: {}", path, e))
+    }
+
+    /// 解析配置文本并做语义校验
+    pub fn parse(content: &str, format: ConfigFormat) -> anyhow::Result<Self> {
+        let config: Config = match format {
+            ConfigFormat::Json => serde_json::from_str(content)?,
+            ConfigFormat::Toml => toml::from_str(content)?,
+            ConfigFormat::Yaml => serde_yaml::from_str(content)?,
+        };
+        config.validate()?;
         Ok(config)
     }
 
-    pub fn merge_with_args(&mut self, args: &crate::cli::args::Args) {
-        // 只有当命令行明确指定格式时才覆盖配置文件设置
+    /// 检查取值范围，错误信息中给出对应的配置项
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.sample_rate == Some(0) {
+            anyhow::bail!("sample_rate 必须大于 0");
+        }
+        if let Some(channels) = self.channels {
+            if !(1..=MAX_CHANNELS).contains(&channels) {
+                anyhow::bail!("channels 必须在 1 到 {} 之间，当前为 {}", MAX_CHANNELS, channels);
+            }
+        }
+        if !self.gain.is_finite() || self.gain.abs() > MAX_GAIN_DB {
+            anyhow::bail!("gain 必须在 -{0} 到 {0} dB 之间，当前为 {1}", MAX_GAIN_DB, self.gain);
+        }
+        for (index, filter) in self.filters.iter().enumerate() {
+            if filter.cutoff.is_some_and(|cutoff| cutoff.is_nan() || cutoff <= 0.0) {
+                anyhow::bail!("filters[{}].cutoff 必须大于 0", index);
+            }
+            if filter.q.is_nan() || filter.q <= 0.0 {
+                anyhow::bail!("filters[{}].q 必须大于 0", index);
+            }
+        }
+        if self.output_settings.values_per_line == 0 {
+            anyhow::bail!("output_settings.values_per_line 必须大于 0");
+        }
+        if let Some(features) = &self.features {
+            features
+                .validate()
+                .map_err(|e| anyhow::anyhow!("features: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// 查找预设：先查配置文件中的自定义预设，再查内置预设
+    pub fn find_preset(&self, name: &str) -> anyhow::Result<Preset> {
+        if let Some(preset) = self.presets.get(name) {
+            return Ok(preset.clone());
+        }
+        presets::builtin(name).ok_or_else(|| {
+            let available: Vec<&str> = presets::BUILTIN_NAMES
+                .iter()
+                .copied()
+                .chain(self.presets.keys().map(|k| k.as_str()))
+                .collect();
+            anyhow::anyhow!("未知的预设: {}（可用: {}）", name, available.join(", "))
+        })
+    }
+
+    /// 合并命令行参数：先应用 `--preset`，再由其他参数覆盖
+    pub fn merge_with_args(&mut self, args: &crate::cli::args::Args) -> anyhow::Result<()> {
+        if let Some(name) = &args.preset {
+            self.apply_preset(name)?;
+        }
+        self.apply_flags(args)
+    }
+
+    /// 应用名为 `name` 的预设
+    pub fn apply_preset(&mut self, name: &str) -> anyhow::Result<()> {
+        self.find_preset(name)?.apply(self);
+        Ok(())
+    }
+
+    /// 合并除 `--preset` 以外的命令行参数
+    pub fn apply_flags(&mut self, args: &crate::cli::args::Args) -> anyhow::Result<()> {
+        // 命令行只覆盖明确指定的字段，未指定的保留配置文件的值
         if let Some(format) = args.format {
             self.output_format = format;
         }
-        
+
+        // `-s source` / `-c source` 恢复为保持源文件
         if let Some(sr) = args.sample_rate {
-            self.sample_rate = Some(sr);
+            self.sample_rate = sr.value();
         }
-        
+
         if let Some(ch) = args.channels {
-            self.channels = Some(ch);
+            self.channels = ch.value();
         }
-        
-        // 只有当命令行明确指定增益时才覆盖配置文件设置
+
         if let Some(gain) = args.gain {
             self.gain = gain;
         }
+
+        if let Some(normalize) = flag_pair(args.normalize, args.no_normalize) {
+            self.normalize = normalize;
+        }
+
+        // 命令行指定的滤波器替换配置文件中的滤波器链
+        if args.no_filters {
+            self.filters.clear();
+        } else if !args.filters.is_empty() {
+            self.filters = args.filters.clone();
+        }
+
+        if let Some(chain) = &args.chain {
+            self.processing_chain = pipeline::parse_chain(chain)?;
+        }
+
+        let output = &mut self.output_settings;
+        if let Some(array_type) = &args.array_type {
+            output.array_type = array_type.clone();
+        }
+
+        if let Some(metadata) = flag_pair(args.metadata, args.no_metadata) {
+            output.include_metadata = metadata;
+        }
+
+        if let Some(compress) = flag_pair(args.compress, args.no_compress) {
+            output.compress = compress;
+        }
+
+        if let Some(wav_format) = args.wav_format {
+            output.wav_format = Some(wav_format);
+        }
+
+        if let Some(layout) = args.layout {
+            output.layout = layout;
+        }
+
+        if let Some(float_format) = args.float_format {
+            output.float_format = float_format;
+        }
+
+        if let Some(precision) = args.precision {
+            output.precision = precision;
+        }
+
+        if let Some(hex) = flag_pair(args.hex, args.no_hex) {
+            output.hex_integers = hex;
+        }
+
+        if let Some(per_line) = args.per_line {
+            output.values_per_line = per_line;
+        }
+
+        if args.no_template {
+            output.template = None;
+        } else if let Some(template) = &args.template {
+            output.template = Some(template.clone());
+        }
+
+        if let Some(endian) = args.endian {
+            output.endianness = endian;
+        }
+
+        if let Some(timestamps) = flag_pair(args.timestamps, args.no_timestamps) {
+            output.csv_timestamps = timestamps;
+        }
+
+        if let Some(name) = &args.name {
+            output.name = Some(name.clone());
+        }
+
+        if args.no_features {
+            self.features = None;
+        } else if let Some(kind) = args.features {
+            match &mut self.features {
+                Some(features) => features.kind = kind,
+                None => self.features = Some(FeatureSettings::new(kind)),
+            }
+        }
+
+        // 特征参数只在启用特征提取时生效
+        if let Some(features) = &mut self.features {
+            if let Some(fft_size) = args.fft_size {
+                features.fft_size = fft_size;
+            }
+            if let Some(hop) = args.hop {
+                features.hop = hop;
+            }
+            if let Some(window) = args.window {
+                features.window = window;
+            }
+            if let Some(mel_bands) = args.mel_bands {
+                features.mel_bands = mel_bands;
+            }
+            if let Some(mfcc) = args.mfcc {
+                features.mfcc = mfcc;
+            }
+        }
+
+        Ok(())
     }
 }
 
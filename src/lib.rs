@@ -7,7 +7,16 @@ pub mod cli;
 pub mod config;
 pub mod output;
 
-pub use audio::{converter::AudioConverter, decoder::AudioDecoder};
-pub use cli::args::{Args, OutputFormat};
-pub use config::settings::Config;
+pub use audio::{converter::{AudioConverter, ConvertState}, decoder::{AudioDecoder, DecodeOptions, NativeSamples}};
+pub use audio::analysis::{analyze, AudioAnalysis};
+pub use audio::features::{extract as extract_features, FeatureSettings, Features};
+pub use audio::peaks::{compute as compute_peaks, PeakSettings, Peaks};
+pub use audio::pipeline::{AudioBuffer, ChannelUsage, ProcessingStage, ProcessingStep};
+pub use audio::probe::{probe_file, AudioInfo};
+pub use cli::args::{ArrayLayout, Args, Endianness, FeatureKind, FloatFormat, OutputFormat, RenderMode, WavFormat, WindowType};
+pub use config::layers::{LayerOptions, LayeredConfig};
+pub use config::settings::{Config, ConfigFormat};
+pub use output::array_reader::{ArrayParams, ArrayReader};
 pub use output::array_writer::ArrayWriter;
+pub use output::audio_writer::AudioWriter;
+pub use output::data_writer::DataWriter;
@@ -1,13 +1,16 @@
-mod audio;
-mod cli;
-mod config;
-mod output;
-
 use clap::{Parser, CommandFactory};
-use cli::args::Args;
-use crate::config::settings::Config;
-use audio::{decoder::AudioDecoder, converter::AudioConverter};
-use output::array_writer::ArrayWriter;
+use audio_converter::cli::build::BuildManifest;
+use audio_converter::cli::convert::{self, ConvertJob, ConvertOptions, JobSource};
+use audio_converter::cli::watch;
+use audio_converter::cli::args::{AnalyzeArgs, Args, BuildArgs, Command, ConfigCommand, ConfigShowArgs, DecodeArrayArgs, FeatureKind, InfoArgs, PeaksArgs, RenderArgs, RenderMode};
+use audio_converter::config::layers::{LayerOptions, LayeredConfig};
+use audio_converter::config::settings::Config;
+use audio_converter::audio::{analysis, decoder::{AudioDecoder, DecodeOptions}, converter::AudioConverter, probe};
+use audio_converter::audio::features::FeatureSettings;
+use audio_converter::audio::peaks::{self, PeakSettings};
+use audio_converter::output::array_reader::{ArrayParams, ArrayReader};
+use audio_converter::output::image_renderer::{self, Rgb, WaveformStyle};
+use audio_converter::output::{array_writer::ArrayWriter, audio_writer::AudioWriter, data_writer::DataWriter};
 use anyhow::Result;
 
 fn main() -> Result<()> {
@@ -23,6 +26,200 @@ fn main() -> Result<()> {
 
     let args = Args::parse();
 
+    match &args.command {
+        Some(Command::Info(info_args)) => run_info(info_args),
+        Some(Command::Analyze(analyze_args)) => run_analyze(analyze_args),
+        Some(Command::DecodeArray(decode_args)) => run_decode_array(decode_args),
+        Some(Command::Peaks(peaks_args)) => run_peaks(peaks_args),
+        Some(Command::Render(render_args)) => run_render(render_args),
+        Some(Command::Build(build_args)) => run_build(build_args),
+        Some(Command::Config(ConfigCommand::Show(show_args))) => run_config_show(show_args),
+        None => run_convert(&args),
+    }
+}
+
+fn run_info(args: &InfoArgs) -> Result<()> {
+    let info = probe::probe_file(&args.input)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&info)?);
+    } else {
+        print!("{}", info);
+    }
+
+    Ok(())
+}
+
+fn run_analyze(args: &AnalyzeArgs) -> Result<()> {
+    // JSON 报告独占标准输出
+    let mut decoder = AudioDecoder::new();
+    decoder.decode_file_with(&args.input, &DecodeOptions { quiet: args.json, ..DecodeOptions::default() })?;
+
+    let report = analysis::analyze(
+        decoder.get_samples(),
+        decoder.get_sample_rate(),
+        decoder.get_channels()
+    );
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", report);
+    }
+
+    Ok(())
+}
+
+fn run_decode_array(args: &DecodeArrayArgs) -> Result<()> {
+    let params = ArrayParams {
+        sample_rate: args.sample_rate,
+        channels: args.channels,
+        format: args.format,
+        layout: args.layout,
+    };
+
+    let audio = ArrayReader::read_file(&args.input, &params)?;
+    println!("解析完成: 采样率={}Hz, 声道数={}, 样本数={}, 格式={:?}",
+             audio.sample_rate,
+             audio.channels,
+             audio.samples.len(),
+             audio.format);
+
+    let wav_format = args
+        .wav_format
+        .unwrap_or_else(|| AudioWriter::default_format(audio.format));
+    AudioWriter::write_to_file(&audio, &args.output, wav_format)?;
+
+    Ok(())
+}
+
+fn run_peaks(args: &PeaksArgs) -> Result<()> {
+    let mut decoder = AudioDecoder::new();
+    decoder.decode_file(&args.input)?;
+
+    let settings = PeakSettings {
+        samples_per_pixel: args.samples_per_pixel,
+        points: args.points,
+        rms: args.rms,
+        bits: args.bits,
+    };
+    let peaks = peaks::compute(
+        decoder.get_samples(),
+        decoder.get_sample_rate(),
+        decoder.get_channels(),
+        &settings
+    )?;
+    println!("峰值计算完成: 点数={}, 声道数={}, 每点帧数={}",
+             peaks.length,
+             peaks.channels,
+             peaks.samples_per_pixel);
+
+    if args.output.to_lowercase().ends_with(".json") {
+        DataWriter::write_peaks(&peaks, &args.output)?;
+    } else if DataWriter::is_data_path(&args.output) || AudioWriter::is_audio_path(&args.output) {
+        anyhow::bail!("峰值数据只能写为 JSON 或数组源码文件: {}", args.output);
+    } else {
+        ArrayWriter::write_peaks(&peaks, &args.output)?;
+    }
+
+    Ok(())
+}
+
+fn run_render(args: &RenderArgs) -> Result<()> {
+    let mut decoder = AudioDecoder::new();
+    decoder.decode_file(&args.input)?;
+
+    // 与转换命令使用相同的处理链，只做重采样和声道转换
+    let config = Config {
+        sample_rate: args.sample_rate,
+        channels: args.channels,
+        ..Config::default()
+    };
+    let converted = AudioConverter::new(config).convert(
+        decoder.get_samples(),
+        decoder.get_sample_rate(),
+        decoder.get_channels()
+    )?;
+
+    let canvas = match args.mode {
+        RenderMode::Waveform => {
+            let style = WaveformStyle {
+                width: args.width,
+                channel_height: args.height,
+                background: Rgb::parse(&args.background)?,
+                color: Rgb::parse(&args.color)?,
+            };
+            image_renderer::render_waveform(&converted.samples, converted.channels, &style)?
+        }
+        RenderMode::Spectrogram => {
+            let settings = FeatureSettings {
+                fft_size: args.fft_size,
+                hop: args.hop,
+                window: args.window,
+                ..FeatureSettings::new(FeatureKind::Stft)
+            };
+            image_renderer::render_spectrogram(
+                &converted.samples,
+                converted.sample_rate,
+                converted.channels,
+                args.width,
+                args.height,
+                &settings,
+            )?
+        }
+    };
+
+    std::fs::write(&args.output, canvas.to_png())?;
+    println!("图像已写入: {} ({}x{})", args.output, canvas.width, canvas.height);
+    Ok(())
+}
+
+fn run_config_show(args: &ConfigShowArgs) -> Result<()> {
+    let mut layered = LayeredConfig::resolve(&LayerOptions::from_env(args.config.clone()))?;
+    if let Some(name) = &args.preset {
+        layered.apply_preset(name)?;
+        layered.config.validate()?;
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&layered.to_json()?)?);
+    } else {
+        print!("{}", layered.describe()?);
+    }
+
+    Ok(())
+}
+
+fn run_build(args: &BuildArgs) -> Result<()> {
+    convert::init_thread_pool(args.jobs)?;
+    let manifest = BuildManifest::from_file(&args.manifest)?;
+    let tasks = manifest.tasks()?;
+    println!("构建清单 {}: {} 个文件", args.manifest, tasks.len());
+
+    if args.dry_run {
+        for task in &tasks {
+            println!("{} → {}", task.job.input.display(), task.job.output.display());
+        }
+        return Ok(());
+    }
+
+    // 缓存清单放在构建清单所在目录
+    let options = ConvertOptions {
+        preview: false,
+        verbose: args.verbose,
+        cache_dir: Some(manifest.root.clone()),
+        force: args.force,
+    };
+    let task_refs: Vec<(&ConvertJob, &Config)> = tasks.iter().map(|task| (&task.job, &task.config)).collect();
+    let summary = convert::convert_tasks(&task_refs, &options);
+    if summary.failed > 0 {
+        anyhow::bail!("{} 个文件中有 {} 个转换失败", tasks.len(), summary.failed);
+    }
+    println!("构建完成！共转换 {} 个文件，{} 个未变化已跳过", summary.converted, summary.skipped);
+    Ok(())
+}
+
+fn run_convert(args: &Args) -> Result<()> {
     // 验证必需参数
     if args.input.is_none() {
         eprintln!("错误: 缺少输入文件参数 (-i 或 --input)");
@@ -36,56 +233,52 @@ fn main() -> Result<()> {
         std::process::exit(1);
     }
 
+    convert::init_thread_pool(args.jobs)?;
+
     // 获取已验证的参数
     let input_path = args.input.as_ref().unwrap();
     let output_path = args.output.as_ref().unwrap();
 
-    // 加载配置
-    let mut config = if let Some(config_path) = &args.config {
-        Config::from_file(config_path)?
-    } else {
-        Config::default()
+    // 依次合并默认值、用户配置、项目配置、-C 配置文件、环境变量和命令行参数
+    let layer_options = LayerOptions::from_env(args.config.clone());
+    let load_config = || -> Result<Config> {
+        let mut layered = LayeredConfig::resolve(&layer_options)?;
+        layered.merge_with_args(args)?;
+        Ok(layered.config)
     };
 
-    // 合并命令行参数
-    config.merge_with_args(&args);
+    let source = JobSource::new(input_path, output_path, args.output_ext.as_deref());
+    // 目录转换在输出目录中维护增量缓存
+    let options = ConvertOptions {
+        preview: args.preview,
+        verbose: args.verbose,
+        cache_dir: source.is_directory().then(|| source.output.clone()),
+        force: args.force,
+    };
 
-    // 如果没有设置增益，使用默认值 0.0
-    if args.gain.is_none() && config.gain == 0.0 {
-        // 保持默认值 0.0
+    if args.watch {
+        let config_files: Vec<_> = layer_options.config_files().into_iter().map(|(_, path)| path).collect();
+        return watch::watch(&source, &config_files, load_config, &options);
     }
 
+    let config = load_config()?;
     if args.verbose {
         println!("配置: {:#?}", config);
     }
 
-    // 解码音频文件
-    println!("正在解码音频文件: {}", input_path);
-    let mut decoder = AudioDecoder::new();
-    decoder.decode_file(input_path)?;
-
-    println!("解码完成: 采样率={}Hz, 声道数={}, 样本数={}", 
-             decoder.get_sample_rate(), 
-             decoder.get_channels(), 
-             decoder.get_samples().len());
-
-    // 转换音频
-    let converter = AudioConverter::new(config);
-    let converted = converter.convert(
-        decoder.get_samples(),
-        decoder.get_sample_rate(),
-        decoder.get_channels()
-    )?;
-
-    println!("转换完成: 采样率={}Hz, 声道数={}, 样本数={}", 
-             converted.sample_rate, 
-             converted.channels, 
-             converted.samples.len());
-
-    // 输出数组
-    ArrayWriter::write_to_file(&converted, output_path)?;
-
-    println!("处理完成！");
+    if source.is_directory() {
+        let jobs = source.jobs()?;
+        let summary = convert::convert_all(&jobs, &config, &options);
+        if summary.failed > 0 {
+            anyhow::bail!("{} 个文件中有 {} 个转换失败", jobs.len(), summary.failed);
+        }
+        println!("处理完成！共转换 {} 个文件，{} 个未变化已跳过", summary.converted, summary.skipped);
+    } else {
+        for job in source.jobs()? {
+            convert::convert_file(&job, &config, &options)?;
+        }
+        println!("处理完成！");
+    }
     Ok(())
 }
 
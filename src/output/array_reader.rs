@@ -0,0 +1,564 @@
+use crate::audio::converter::ConvertedAudio;
+use crate::cli::args::{ArrayLayout, OutputFormat};
+use crate::output::{number_format, sample_format};
+use anyhow::Result;
+
+/// 元数据注释的前缀（与 ArrayWriter 的输出一致）
+const METADATA_PREFIX: &str = "音频元数据:";
+
+/// 用户提供的数组参数，优先于文件中的元数据
+#[derive(Debug, Clone, Default)]
+pub struct ArrayParams {
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u32>,
+    pub format: Option<OutputFormat>,
+    pub layout: Option<ArrayLayout>,
+}
+
+/// 将数组源码（ArrayWriter 的输出或通用的 C/Rust/Python 数组字面量）解析回音频样本
+pub struct ArrayReader;
+
+impl ArrayReader {
+    pub fn read_file(path: &str, params: &ArrayParams) -> Result<ConvertedAudio> {
+        let content = std::fs::read_to_string(path)?;
+        Self::parse(&content, params)
+    }
+
+    pub fn parse(content: &str, params: &ArrayParams) -> Result<ConvertedAudio> {
+        let metadata = Self::parse_metadata(content);
+        let meta_u32 = |key: &str| {
+            metadata
+                .as_ref()
+                .and_then(|m| m.get(key))
+                .and_then(|v| v.as_u64())
+                .map(|v| v as u32)
+        };
+        let meta_format = metadata
+            .as_ref()
+            .and_then(|m| m.get("format"))
+            .and_then(|v| v.as_str())
+            .and_then(OutputFormat::from_name);
+        let layout = params
+            .layout
+            .or_else(|| {
+                metadata
+                    .as_ref()
+                    .and_then(|m| m.get("layout"))
+                    .and_then(|v| v.as_str())
+                    .and_then(ArrayLayout::from_name)
+            })
+            .unwrap_or_default();
+
+        // 每个声道一个数组时需要读取文件中的全部数组
+        let all_arrays = layout == ArrayLayout::PerChannel;
+        let (values, has_float) = Self::parse_values(content, all_arrays)?;
+        if values.is_empty() {
+            anyhow::bail!("未在文件中找到数组数据");
+        }
+
+        let format = params
+            .format
+            .or(meta_format)
+            .unwrap_or_else(|| Self::infer_format(&values, has_float));
+        let values = if format == OutputFormat::I24Packed {
+            Self::unpack_i24(&values)?
+        } else {
+            values
+        };
+
+        let sample_rate = params
+            .sample_rate
+            .or_else(|| meta_u32("sample_rate"))
+            .ok_or_else(|| anyhow::anyhow!("文件中没有元数据，请通过 --sample-rate 指定采样率"))?;
+        let channels = params.channels.or_else(|| meta_u32("channels")).unwrap_or(1);
+        if channels == 0 {
+            anyhow::bail!("声道数必须大于 0");
+        }
+        if values.len() % channels as usize != 0 {
+            anyhow::bail!("样本数 {} 不能被声道数 {} 整除", values.len(), channels);
+        }
+
+        let values = match layout {
+            ArrayLayout::Planar | ArrayLayout::PerChannel => {
+                Self::interleave(&values, channels as usize)
+            }
+            ArrayLayout::Interleaved | ArrayLayout::TwoD => values,
+        };
+
+        let samples = values
+            .iter()
+            .map(|&v| sample_format::decode(v, format))
+            .collect();
+
+        Ok(ConvertedAudio {
+            samples,
+            sample_rate,
+            channels,
+            format,
+        })
+    }
+
+    /// 读取 `// 音频元数据: {...}` 注释中的 JSON
+    fn parse_metadata(content: &str) -> Option<serde_json::Value> {
+        content.lines().find_map(|line| {
+            let (_, rest) = line.split_once(METADATA_PREFIX)?;
+            serde_json::from_str(rest.trim()).ok()
+        })
+    }
+
+    /// 没有元数据也未指定格式时：含小数的视为浮点，否则按数值范围选择整数位宽
+    fn infer_format(values: &[f64], has_float: bool) -> OutputFormat {
+        if has_float {
+            OutputFormat::F32
+        } else if values.iter().all(|v| v.abs() <= i16::MAX as f64 + 1.0) {
+            OutputFormat::I16
+        } else {
+            OutputFormat::I32
+        }
+    }
+
+    /// 每 3 个字节（小端序）组成一个 24 位样本
+    fn unpack_i24(bytes: &[f64]) -> Result<Vec<f64>> {
+        if !bytes.len().is_multiple_of(3) {
+            anyhow::bail!("i24-packed 数据的字节数 {} 不是 3 的倍数", bytes.len());
+        }
+
+        bytes
+            .chunks_exact(3)
+            .map(|chunk| {
+                let mut packed = [0u8; 3];
+                for (byte, &value) in packed.iter_mut().zip(chunk) {
+                    if !(0.0..=255.0).contains(&value) {
+                        anyhow::bail!("i24-packed 数据中的字节超出范围: {}", value);
+                    }
+                    *byte = value as u8;
+                }
+                Ok(sample_format::unpack_i24(packed) as f64)
+            })
+            .collect()
+    }
+
+    /// 平面排列（按声道分段）的数据转换为交错排列
+    fn interleave(values: &[f64], channels: usize) -> Vec<f64> {
+        let frames = values.len() / channels;
+        (0..values.len())
+            .map(|i| values[(i % channels) * frames + i / channels])
+            .collect()
+    }
+
+    /// 提取数组字面量中的全部数值，返回数值及是否出现了浮点写法
+    fn parse_values(content: &str, all_arrays: bool) -> Result<(Vec<f64>, bool)> {
+        let code = strip_comments(content);
+        let mut bodies = array_bodies(&code);
+        if !all_arrays {
+            bodies.truncate(1);
+        }
+        if bodies.is_empty() {
+            // 没有赋值语句时把整个文本当作数组内容
+            bodies.push(code.as_str());
+        }
+
+        let mut values = Vec::new();
+        let mut has_float = false;
+        // `f32::from_bits(0x...)` 被拆成两个词，记录下一个数值是哪种位模式
+        let mut pending_bits: Option<bool> = None;
+        for token in bodies
+            .iter()
+            .flat_map(|body| body.split(|c: char| c.is_whitespace() || "[]{}(),;".contains(c)))
+            .filter(|t| !t.is_empty())
+        {
+            if let Some(kind) = token.strip_suffix("::from_bits") {
+                pending_bits = Some(kind.ends_with("f32"));
+                continue;
+            }
+
+            let (value, is_float) = parse_number(token)
+                .ok_or_else(|| anyhow::anyhow!("无法解析数组元素: {}", token))?;
+            let (value, is_float) = match pending_bits.take() {
+                Some(true) => (f32::from_bits(value as u32) as f64, true),
+                Some(false) => (f64::from_bits(value as u64), true),
+                None => (value, is_float),
+            };
+            has_float |= is_float;
+            values.push(value);
+        }
+
+        Ok((values, has_float))
+    }
+}
+
+/// 去除 `//`、`/* */` 和 `#` 注释
+fn strip_comments(content: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        result.push('\n');
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                result.push(' ');
+            }
+            '#' => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        result.push('\n');
+                        break;
+                    }
+                }
+            }
+            _ => result.push(c),
+        }
+    }
+
+    result
+}
+
+/// 定位所有数组字面量：`=` 之后、同一语句内的第一个括号中的内容
+///
+/// `=` 与括号之间出现 `;` 或另一个 `=` 时说明这条赋值不是数组（例如长度常量），跳过。
+fn array_bodies(code: &str) -> Vec<&str> {
+    let mut bodies = Vec::new();
+    let mut search_from = 0;
+
+    while let Some(offset) = code[search_from..].find('=') {
+        let start = search_from + offset + 1;
+        search_from = start;
+
+        let open = match code[start..].find(['[', '{']) {
+            Some(offset) => start + offset,
+            None => break,
+        };
+        if code[start..open].contains([';', '=']) {
+            continue;
+        }
+
+        let close = matching_bracket(code, open);
+        bodies.push(&code[open + 1..close]);
+        search_from = close;
+    }
+
+    bodies
+}
+
+/// 返回与 `open` 处括号匹配的闭括号位置，未闭合时返回文本末尾
+fn matching_bracket(code: &str, open: usize) -> usize {
+    let mut depth = 0usize;
+    for (i, c) in code[open..].char_indices() {
+        match c {
+            '[' | '{' => depth += 1,
+            ']' | '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return open + i;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    code.len()
+}
+
+/// 解析单个数值字面量，支持十六进制整数、十六进制浮点、下划线分隔和 Rust/C 类型后缀
+fn parse_number(token: &str) -> Option<(f64, bool)> {
+    let token = token.replace('_', "").to_lowercase();
+    let (negative, digits) = match token.strip_prefix('-') {
+        Some(rest) => (true, rest.to_string()),
+        None => (false, token.trim_start_matches('+').to_string()),
+    };
+    let sign = if negative { -1.0 } else { 1.0 };
+
+    if digits.starts_with("0x") {
+        if digits.contains('p') {
+            return number_format::parse_hex_float(&digits).map(|v| (sign * v, true));
+        }
+
+        // 十六进制中 f 是数字，只去掉整数类型后缀
+        let mut hex = digits.as_str();
+        for suffix in ["i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64"] {
+            if let Some(stripped) = hex.strip_suffix(suffix) {
+                hex = stripped;
+                break;
+            }
+        }
+        let hex = hex[2..].trim_end_matches(['u', 'l']);
+        return u64::from_str_radix(hex, 16).ok().map(|v| (sign * v as f64, false));
+    }
+
+    // Rust 类型后缀，例如 0.5f32、-12i16
+    let mut digits = digits.as_str();
+    for suffix in ["f32", "f64", "i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64"] {
+        if let Some(stripped) = digits.strip_suffix(suffix) {
+            digits = stripped;
+            break;
+        }
+    }
+
+    // C 后缀，例如 0.5f、100L、10u
+    let digits = match digits {
+        "inf" | "nan" => digits,
+        _ => digits.trim_end_matches(['f', 'l', 'u']),
+    };
+    let is_float = digits.contains(['.', 'e']) || digits == "inf" || digits == "nan";
+    digits.parse::<f64>().ok().map(|v| (sign * v, is_float))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::args::FloatFormat;
+    use crate::output::array_writer::ArrayWriter;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_number() {
+        assert_eq!(parse_number("12"), Some((12.0, false)));
+        assert_eq!(parse_number("-0.5"), Some((-0.5, true)));
+        assert_eq!(parse_number("1e-3"), Some((0.001, true)));
+        assert_eq!(parse_number("0.25f"), Some((0.25, true)));
+        assert_eq!(parse_number("0.25f32"), Some((0.25, true)));
+        assert_eq!(parse_number("-32768i16"), Some((-32768.0, false)));
+        assert_eq!(parse_number("0x7FFF"), Some((32767.0, false)));
+        assert_eq!(parse_number("-0x10"), Some((-16.0, false)));
+        assert_eq!(parse_number("1_000"), Some((1000.0, false)));
+        assert_eq!(parse_number("0x0F32"), Some((3890.0, false)));
+        assert_eq!(parse_number("0x10i16"), Some((16.0, false)));
+        assert_eq!(parse_number("-0x1.8p-1"), Some((-0.75, true)));
+        assert_eq!(parse_number("abc"), None);
+    }
+
+    #[test]
+    fn test_round_trip_array_writer_output() {
+        let temp_dir = TempDir::new().unwrap();
+        let samples: Vec<f32> = (0..64).map(|i| (i as f32 * 0.3).sin() * 0.8).collect();
+
+        for format in [
+            OutputFormat::F32,
+            OutputFormat::F64,
+            OutputFormat::I8,
+            OutputFormat::U8,
+            OutputFormat::I16,
+            OutputFormat::U16,
+            OutputFormat::I24,
+            OutputFormat::I24Packed,
+            OutputFormat::I32,
+            OutputFormat::Q15,
+            OutputFormat::Q31,
+        ] {
+            let tolerance = match format.bits() {
+                Some(8) => 1e-2,
+                _ => 1e-4,
+            };
+            let audio = ConvertedAudio {
+                samples: samples.clone(),
+                sample_rate: 16000,
+                channels: 2,
+                format,
+            };
+            let path = temp_dir.path().join(format!("{:?}.rs", format));
+            let path = path.to_str().unwrap();
+            ArrayWriter::write_to_file(&audio, path).unwrap();
+
+            let decoded = ArrayReader::read_file(path, &ArrayParams::default()).unwrap();
+            assert_eq!(decoded.sample_rate, 16000);
+            assert_eq!(decoded.channels, 2);
+            assert_eq!(decoded.format, format);
+            assert_eq!(decoded.samples.len(), samples.len());
+            for (a, b) in decoded.samples.iter().zip(&samples) {
+                assert!((a - b).abs() < tolerance, "{:?}: {} vs {}", format, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_c_array() {
+        let content = r#"
+            #include <stdint.h>
+            /* generated */
+            static const int16_t sound[4] = {
+                0, 16384, -16384, // comment
+                32767
+            };
+        "#;
+        let params = ArrayParams {
+            sample_rate: Some(8000),
+            ..Default::default()
+        };
+
+        let audio = ArrayReader::parse(content, &params).unwrap();
+        assert_eq!(audio.format, OutputFormat::I16);
+        assert_eq!(audio.channels, 1);
+        assert_eq!(audio.samples.len(), 4);
+        assert_eq!(audio.samples[1], 0.5);
+        assert_eq!(audio.samples[2], -0.5);
+    }
+
+    #[test]
+    fn test_parse_unsigned_and_packed() {
+        let params = ArrayParams {
+            sample_rate: Some(8000),
+            format: Some(OutputFormat::U8),
+            ..Default::default()
+        };
+        let audio = ArrayReader::parse("uint8_t x[] = {0, 128, 192};", &params).unwrap();
+        assert_eq!(audio.samples, vec![-1.0, 0.0, 0.5]);
+
+        let params = ArrayParams {
+            format: Some(OutputFormat::I24Packed),
+            ..params
+        };
+        let audio = ArrayReader::parse("x = [0x00, 0x00, 0x80, 0xFF, 0xFF, 0x7F]", &params).unwrap();
+        assert_eq!(audio.samples.len(), 2);
+        assert_eq!(audio.samples[0], -1.0);
+        assert!(ArrayReader::parse("x = [0, 0]", &params).is_err());
+    }
+
+    #[test]
+    fn test_round_trip_layouts() {
+        let temp_dir = TempDir::new().unwrap();
+        let samples: Vec<f32> = (0..30).map(|i| (i as f32 * 0.7).sin() * 0.9).collect();
+
+        for layout in [ArrayLayout::Interleaved, ArrayLayout::Planar, ArrayLayout::PerChannel, ArrayLayout::TwoD] {
+            for channels in [1, 2, 3] {
+                let audio = ConvertedAudio {
+                    samples: samples.clone(),
+                    sample_rate: 8000,
+                    channels,
+                    format: OutputFormat::I24Packed,
+                };
+                let settings = crate::config::settings::OutputSettings {
+                    layout,
+                    ..Default::default()
+                };
+                let path = temp_dir.path().join(format!("{:?}_{}.rs", layout, channels));
+                let path = path.to_str().unwrap();
+                ArrayWriter::write_with_settings(&audio, path, &settings).unwrap();
+
+                let decoded = ArrayReader::read_file(path, &ArrayParams::default()).unwrap();
+                assert_eq!(decoded.channels, channels);
+                assert_eq!(decoded.samples.len(), samples.len(), "{:?}/{}", layout, channels);
+                for (a, b) in decoded.samples.iter().zip(&samples) {
+                    assert!((a - b).abs() < 1e-6, "{:?}/{}: {} vs {}", layout, channels, a, b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_trip_number_styles() {
+        let samples: Vec<f32> = (0..20).map(|i| (i as f32 * 0.37).sin() * 0.99).collect();
+
+        for float_format in [FloatFormat::Shortest, FloatFormat::HexFloat, FloatFormat::Bits] {
+            for format in [OutputFormat::F32, OutputFormat::F64] {
+                let audio = ConvertedAudio {
+                    samples: samples.clone(),
+                    sample_rate: 8000,
+                    channels: 1,
+                    format,
+                };
+                let settings = crate::config::settings::OutputSettings {
+                    float_format,
+                    values_per_line: 6,
+                    ..Default::default()
+                };
+                // 十六进制浮点只能写入 C 源码，模板输出不带元数据前缀
+                let (content, params) = if float_format == FloatFormat::HexFloat {
+                    let template = crate::output::template::load("builtin:c-header").unwrap();
+                    let params = ArrayParams {
+                        sample_rate: Some(8000),
+                        format: Some(format),
+                        ..Default::default()
+                    };
+                    (ArrayWriter::render_template(&audio, &settings, &template, "beep").unwrap(), params)
+                } else {
+                    (ArrayWriter::render(&audio, &settings).unwrap(), ArrayParams::default())
+                };
+
+                let decoded = ArrayReader::parse(&content, &params).unwrap();
+                assert_eq!(decoded.samples, samples, "{:?}/{:?}", float_format, format);
+            }
+        }
+
+        let audio = ConvertedAudio {
+            samples: samples.clone(),
+            sample_rate: 8000,
+            channels: 2,
+            format: OutputFormat::I16,
+        };
+        let settings = crate::config::settings::OutputSettings {
+            hex_integers: true,
+            values_per_line: 8,
+            ..Default::default()
+        };
+        let content = ArrayWriter::render(&audio, &settings).unwrap();
+        let decoded = ArrayReader::parse(&content, &ArrayParams::default()).unwrap();
+        for (a, b) in decoded.samples.iter().zip(&samples) {
+            assert!((a - b).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_parse_python_list() {
+        let content = "samples = np.array([0.0, 0.5, -0.5, 1.0e-1], dtype=np.float32)\n";
+        let params = ArrayParams {
+            sample_rate: Some(44100),
+            channels: Some(2),
+            ..Default::default()
+        };
+
+        let audio = ArrayReader::parse(content, &params).unwrap();
+        assert_eq!(audio.format, OutputFormat::F32);
+        assert_eq!(audio.channels, 2);
+        assert_eq!(audio.samples, vec![0.0, 0.5, -0.5, 0.1]);
+    }
+
+    #[test]
+    fn test_params_override_metadata() {
+        let content = "// 音频元数据: {\"channels\":2,\"format\":\"i16\",\"length\":2,\"sample_rate\":44100}\n\
+                       const AUDIO_SAMPLES: [i16; 2] = [\n    100,\n    -100\n];";
+        let params = ArrayParams {
+            sample_rate: Some(8000),
+            channels: Some(1),
+            ..Default::default()
+        };
+
+        let audio = ArrayReader::parse(content, &params).unwrap();
+        assert_eq!(audio.sample_rate, 8000);
+        assert_eq!(audio.channels, 1);
+        assert_eq!(audio.format, OutputFormat::I16);
+    }
+
+    #[test]
+    fn test_missing_sample_rate_and_bad_data() {
+        assert!(ArrayReader::parse("x = [1, 2, 3]", &ArrayParams::default()).is_err());
+
+        let params = ArrayParams {
+            sample_rate: Some(8000),
+            ..Default::default()
+        };
+        assert!(ArrayReader::parse("x = [1, two, 3]", &params).is_err());
+        assert!(ArrayReader::parse("x = []", &params).is_err());
+
+        let params = ArrayParams {
+            sample_rate: Some(8000),
+            channels: Some(2),
+            ..Default::default()
+        };
+        assert!(ArrayReader::parse("x = [1, 2, 3]", &params).is_err());
+    }
+}
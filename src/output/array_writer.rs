@@ -1,17 +1,44 @@
 use crate::audio::converter::ConvertedAudio;
-use crate::cli::args::OutputFormat;
+use crate::audio::pipeline::PARALLEL_CHUNK;
+use crate::audio::features::{self, Features};
+use crate::audio::peaks::Peaks;
+use crate::cli::args::{ArrayLayout, OutputFormat};
+use crate::config::settings::OutputSettings;
+use crate::output::template::{self, TemplateContext};
+use crate::output::number_format::{self, Language};
+use crate::output::sample_format::{self, Sample};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::path::Path;
 use anyhow::Result;
 use serde_json::json;
 
 pub struct ArrayWriter;
 
 impl ArrayWriter {
-    pub fn write_to_file(audio: &ConvertedAudio, output_path: &str) -> Result<()> {
-        let output = match audio.format {
-            OutputFormat::F32 => Self::create_f32_output(audio),
-            OutputFormat::F64 => Self::create_f64_output(audio),
-            OutputFormat::I16 => Self::create_i16_output(audio),
-            OutputFormat::I32 => Self::create_i32_output(audio),
+    pub fn write_to_file<S: Sample>(audio: &ConvertedAudio<S>, output_path: &str) -> Result<()> {
+        Self::write_with_settings(audio, output_path, &OutputSettings::default())
+    }
+
+    pub fn write_with_settings<S: Sample>(audio: &ConvertedAudio<S>, output_path: &str, settings: &OutputSettings) -> Result<()> {
+        let output = match &settings.template {
+            Some(template_path) => {
+                // 自定义模板的语言未知，浮点写法由模板作者负责
+                if let Some(language) = template::language(template_path) {
+                    if audio.format.bits().is_none() {
+                        number_format::check_float_format(settings.float_format, language)?;
+                    }
+                }
+                let template = template::load(template_path)?;
+                let name = settings.name.as_deref().unwrap_or_else(|| {
+                    Path::new(output_path)
+                        .file_stem()
+                        .and_then(|s| s.to_str())
+                        .unwrap_or("audio")
+                });
+                Self::render_template(audio, settings, &template, name)?
+            }
+            None => Self::render(audio, settings)?,
         };
 
         std::fs::write(output_path, output)?;
@@ -19,95 +46,593 @@ impl ArrayWriter {
         Ok(())
     }
 
-    fn create_f32_output(audio: &ConvertedAudio) -> String {
-        let metadata = json!({
-            "sample_rate": audio.sample_rate,
-            "channels": audio.channels,
-            "length": audio.samples.len(),
-            "format": "f32"
-        });
+    /// 生成完整的数组源码：元数据注释、长度常量和按布局排列的数组
+    ///
+    /// 浮点写法不是合法的 Rust 字面量（hex-float）时返回错误。
+    pub fn render<S: Sample>(audio: &ConvertedAudio<S>, settings: &OutputSettings) -> Result<String> {
+        let format = audio.format;
+        if format.bits().is_none() {
+            number_format::check_float_format(settings.float_format, Language::Rust)?;
+        }
+        let layout = settings.layout;
+        let channels = audio.channels.max(1) as usize;
+        let frames = audio.samples.len() / channels;
+        // i24-packed 每个样本占 3 个数组元素
+        let width = if format == OutputFormat::I24Packed { 3 } else { 1 };
+        let values = Self::format_samples(audio, settings);
+        let per_line = settings.values_per_line.max(1);
+        // 常量名前缀，默认为 AUDIO
+        let prefix = settings
+            .name
+            .as_deref()
+            .map(|name| template::identifier(name).to_uppercase())
+            .unwrap_or_else(|| "AUDIO".to_string());
+        let samples_name = format!("{}_SAMPLES", prefix);
 
-        format!(
-            "// 音频元数据: {}\n// 样本数组 (f32 格式)\nconst AUDIO_SAMPLES: [f32; {}] = [\n{}\n];",
-            metadata,
-            audio.samples.len(),
-            audio.samples
-                .iter()
-                .map(|s| format!("    {:.6}", s))
+        let mut output = String::new();
+        if settings.include_metadata {
+            let metadata = json!({
+                "sample_rate": audio.sample_rate,
+                "channels": audio.channels,
+                "length": audio.samples.len(),
+                "frames": frames,
+                "format": format.name(),
+                "layout": layout.name()
+            });
+            output.push_str(&format!("// 音频元数据: {}\n", metadata));
+        }
+        output.push_str(&format!("// 样本数组 ({})\n", Self::describe(format, layout)));
+        output.push_str(&format!(
+            "const {0}_FRAMES: usize = {1};\nconst {0}_CHANNELS: usize = {2};\nconst {0}_LENGTH: usize = {3};\n\n",
+            prefix,
+            frames,
+            channels,
+            audio.samples.len()
+        ));
+
+        let element_type = format.rust_type();
+        let array = match layout {
+            ArrayLayout::Interleaved => {
+                Self::array_decl(&samples_name, element_type, values.len() * width, &values, per_line)
+            }
+            ArrayLayout::Planar => {
+                let planar: Vec<String> = (0..channels)
+                    .flat_map(|ch| Self::channel_values(&values, channels, ch))
+                    .collect();
+                Self::array_decl(&samples_name, element_type, planar.len() * width, &planar, per_line)
+            }
+            ArrayLayout::PerChannel => (0..channels)
+                .map(|ch| {
+                    let channel: Vec<String> = Self::channel_values(&values, channels, ch).collect();
+                    let name = Self::channel_name(&prefix, ch, channels);
+                    Self::array_decl(&name, element_type, frames * width, &channel, per_line)
+                })
                 .collect::<Vec<_>>()
-                .join(",\n")
-        )
-    }
+                .join("\n\n"),
+            ArrayLayout::TwoD => {
+                let rows: Vec<String> = values
+                    .chunks(channels)
+                    .map(|frame| format!("[{}]", frame.join(", ")))
+                    .collect();
+                let row_type = format!("[{}; {}]", element_type, channels * width);
+                Self::array_decl(&samples_name, &row_type, frames, &rows, per_line)
+            }
+        };
 
-    fn create_f64_output(audio: &ConvertedAudio) -> String {
-        let samples_f64: Vec<f64> = audio.samples.iter().map(|&s| s as f64).collect();
-        let metadata = json!({
-            "sample_rate": audio.sample_rate,
-            "channels": audio.channels,
-            "length": samples_f64.len(),
-            "format": "f64"
-        });
+        output.push_str(&array);
+        Ok(output)
+    }
 
-        format!(
-            "// 音频元数据: {}\n// 样本数组 (f64 格式)\nconst AUDIO_SAMPLES: [f64; {}] = [\n{}\n];",
-            metadata,
-            samples_f64.len(),
-            samples_f64
-                .iter()
-                .map(|s| format!("    {:.6}", s))
-                .collect::<Vec<_>>()
-                .join(",\n")
-        )
+    pub fn write_features(features: &Features, format: OutputFormat, output_path: &str, settings: &OutputSettings) -> Result<()> {
+        std::fs::write(output_path, Self::render_features(features, format, settings)?)?;
+        println!("特征已写入: {}", output_path);
+        Ok(())
     }
 
-    fn create_i16_output(audio: &ConvertedAudio) -> String {
-        let samples_i16: Vec<i16> = audio.samples
-            .iter()
-            .map(|&s| (s.clamp(-1.0, 1.0) * 32767.0) as i16)
+    /// 特征矩阵写为 `[[f32; 维数]; 帧数]`（或 f64）二维数组，元数据中记录形状
+    ///
+    /// 常量名前缀默认为 FEATURE（数组为 FEATURES），设置 `name` 后为 `<NAME>_FRAMES`、`<NAME>_FEATURES` 等。
+    pub fn render_features(features: &Features, format: OutputFormat, settings: &OutputSettings) -> Result<String> {
+        features::check_output(format, settings)?;
+        number_format::check_float_format(settings.float_format, Language::Rust)?;
+        let (prefix, array_name) = match settings.name.as_deref() {
+            Some(name) => {
+                let prefix = template::identifier(name).to_uppercase();
+                let array_name = format!("{}_FEATURES", prefix);
+                (prefix, array_name)
+            }
+            None => ("FEATURE".to_string(), "FEATURES".to_string()),
+        };
+        let (element_type, is_f32) = (format.rust_type(), format == OutputFormat::F32);
+        let rows: Vec<String> = (0..features.frames)
+            .map(|frame| {
+                let values: Vec<String> = features
+                    .row(frame)
+                    .iter()
+                    .map(|&v| number_format::format_float(v as f64, is_f32, settings.float_format, settings.precision))
+                    .collect();
+                format!("[{}]", values.join(", "))
+            })
             .collect();
-        
-        let metadata = json!({
-            "sample_rate": audio.sample_rate,
-            "channels": audio.channels,
-            "length": samples_i16.len(),
-            "format": "i16"
-        });
 
-        format!(
-            "// 音频元数据: {}\n// 样本数组 (i16 格式)\nconst AUDIO_SAMPLES: [i16; {}] = [\n{}\n];",
-            metadata,
-            samples_i16.len(),
-            samples_i16
-                .iter()
-                .map(|s| format!("    {}", s))
-                .collect::<Vec<_>>()
-                .join(",\n")
-        )
+        let mut output = String::new();
+        if settings.include_metadata {
+            output.push_str(&format!("// 特征元数据: {}\n", features.metadata()));
+        }
+        output.push_str(&format!(
+            "// 特征矩阵 ({}，[帧][维数])\nconst {}_FRAMES: usize = {};\nconst {}_BINS: usize = {};\n\n{}",
+            features.settings.kind.name(),
+            prefix,
+            features.frames,
+            prefix,
+            features.bins,
+            Self::array_decl(
+                &array_name,
+                &format!("[{}; {}]", element_type, features.bins),
+                features.frames,
+                &rows,
+                settings.values_per_line.max(1),
+            )
+        ));
+        Ok(output)
     }
 
-    fn create_i32_output(audio: &ConvertedAudio) -> String {
-        let samples_i32: Vec<i32> = audio.samples
-            .iter()
-            .map(|&s| (s.clamp(-1.0, 1.0) * 2147483647.0) as i32)
-            .collect();
-        
+    pub fn write_peaks(peaks: &Peaks, output_path: &str) -> Result<()> {
+        std::fs::write(output_path, Self::render_peaks(peaks))?;
+        println!("峰值数据已写入: {}", output_path);
+        Ok(())
+    }
+
+    /// 峰值数据写为紧凑数组，每行一个点（各声道的 min、max），可选附带 RMS 数组
+    pub fn render_peaks(peaks: &Peaks) -> String {
+        let element_type = if peaks.bits == 8 { "i8" } else { "i16" };
+        let per_line = peaks.channels.max(1) as usize;
+        let to_strings = |values: Vec<i32>| -> Vec<String> {
+            values.iter().map(|v| v.to_string()).collect()
+        };
+
+        let data = to_strings(peaks.data());
+        let mut output = format!(
+            "// 波形峰值元数据: {}\n// 峰值数组 ({}，[点][声道][min, max])\nconst PEAKS_LENGTH: usize = {};\nconst PEAKS_CHANNELS: usize = {};\n\n{}",
+            peaks.metadata(),
+            element_type,
+            peaks.length,
+            peaks.channels,
+            Self::array_decl("PEAKS", element_type, data.len(), &data, per_line * 2)
+        );
+
+        if let Some(rms) = peaks.rms_data() {
+            let rms = to_strings(rms);
+            output.push_str("\n\n");
+            output.push_str(&Self::array_decl("PEAKS_RMS", element_type, rms.len(), &rms, per_line));
+        }
+
+        output
+    }
+
+    /// 使用自定义模板生成输出，`name` 用于生成标识符
+    pub fn render_template<S: Sample>(
+        audio: &ConvertedAudio<S>,
+        settings: &OutputSettings,
+        template: &str,
+        name: &str,
+    ) -> Result<String> {
+        let format = audio.format;
+        let channels = audio.channels.max(1) as usize;
+        let frames = audio.samples.len() / channels;
+        let values = Self::format_samples(audio, settings);
+        // 模板中的样本按布局排列：平面/分声道为按声道分段，其余为交错
+        let values: Vec<String> = match settings.layout {
+            ArrayLayout::Planar | ArrayLayout::PerChannel => (0..channels)
+                .flat_map(|ch| Self::channel_values(&values, channels, ch))
+                .collect(),
+            ArrayLayout::Interleaved | ArrayLayout::TwoD => values,
+        };
+        let width = if format == OutputFormat::I24Packed { 3 } else { 1 };
+
         let metadata = json!({
             "sample_rate": audio.sample_rate,
             "channels": audio.channels,
-            "length": samples_i32.len(),
-            "format": "i32"
+            "length": audio.samples.len(),
+            "frames": frames,
+            "format": format.name(),
+            "layout": settings.layout.name()
         });
+        let ident = template::identifier(name);
+
+        let variables: HashMap<String, String> = [
+            ("sample_rate", audio.sample_rate.to_string()),
+            ("channels", channels.to_string()),
+            ("frames", frames.to_string()),
+            ("length", audio.samples.len().to_string()),
+            ("elements", (audio.samples.len() * width).to_string()),
+            ("format", format.name().to_string()),
+            ("type", format.rust_type().to_string()),
+            ("c_type", format.c_type().to_string()),
+            ("layout", settings.layout.name().to_string()),
+            ("metadata", metadata.to_string()),
+            ("NAME", ident.to_uppercase()),
+            ("name", ident),
+            ("samples", values.join(", ")),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+
+        template::render(template, &TemplateContext { variables, values })
+    }
+
+    /// 每个样本格式化后的文本（i24-packed 为 3 个字节）；每行多个数值时右对齐到相同宽度
+    ///
+    /// 量化（向量化内核）和格式化分块并行，结果顺序与样本顺序一致。
+    fn format_samples<S: Sample>(audio: &ConvertedAudio<S>, settings: &OutputSettings) -> Vec<String> {
+        let format = audio.format;
+        let elements: Vec<Vec<String>> = match sample_format::encode_all(&audio.samples, format) {
+            None => audio.samples
+                .par_iter()
+                .with_min_len(PARALLEL_CHUNK)
+                .map(|&s| {
+                    vec![number_format::format_float(
+                        s.to_f32() as f64,
+                        format == OutputFormat::F32,
+                        settings.float_format,
+                        settings.precision,
+                    )]
+                })
+                .collect(),
+            Some(values) if format == OutputFormat::I24Packed => values
+                .par_iter()
+                .with_min_len(PARALLEL_CHUNK)
+                .map(|&v| {
+                    sample_format::pack_i24(v)
+                        .iter()
+                        .map(|&b| number_format::format_int(b as i64, 8, settings.hex_integers))
+                        .collect()
+                })
+                .collect(),
+            Some(values) => {
+                let bits = format.bits().unwrap_or(32);
+                values
+                    .par_iter()
+                    .with_min_len(PARALLEL_CHUNK)
+                    .map(|&v| vec![number_format::format_int(v, bits, settings.hex_integers)])
+                    .collect()
+            }
+        };
+
+        let width = if settings.values_per_line > 1 {
+            elements
+                .par_iter()
+                .with_min_len(PARALLEL_CHUNK)
+                .flat_map_iter(|sample| sample.iter().map(|e| e.len()))
+                .max()
+                .unwrap_or(0)
+        } else {
+            0
+        };
+
+        elements
+            .par_iter()
+            .with_min_len(PARALLEL_CHUNK)
+            .map(|sample| {
+                sample
+                    .iter()
+                    .map(|e| format!("{:>width$}", e, width = width))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .collect()
+    }
+
+    fn describe(format: OutputFormat, layout: ArrayLayout) -> String {
+        let format_desc = match format {
+            OutputFormat::I24Packed => "i24-packed 格式，每样本 3 字节，小端序".to_string(),
+            format => format!("{} 格式", format.name()),
+        };
+
+        match layout {
+            ArrayLayout::Interleaved => format_desc,
+            ArrayLayout::Planar => format!("{}，按声道平面排列", format_desc),
+            ArrayLayout::PerChannel => format!("{}，每个声道一个数组", format_desc),
+            ArrayLayout::TwoD => format!("{}，[帧][声道] 二维数组", format_desc),
+        }
+    }
+
+    fn channel_values(values: &[String], channels: usize, channel: usize) -> impl Iterator<Item = String> + '_ {
+        values.iter().skip(channel).step_by(channels).cloned()
+    }
+
+    /// 立体声使用 AUDIO_LEFT/AUDIO_RIGHT，其他声道数使用 AUDIO_CHANNEL_n（前缀可由 `name` 指定）
+    fn channel_name(prefix: &str, channel: usize, channels: usize) -> String {
+        match (channels, channel) {
+            (1, _) => format!("{}_SAMPLES", prefix),
+            (2, 0) => format!("{}_LEFT", prefix),
+            (2, _) => format!("{}_RIGHT", prefix),
+            _ => format!("{}_CHANNEL_{}", prefix, channel),
+        }
+    }
 
+    fn array_decl(name: &str, element_type: &str, len: usize, values: &[String], per_line: usize) -> String {
         format!(
-            "// 音频元数据: {}\n// 样本数组 (i32 格式)\nconst AUDIO_SAMPLES: [i32; {}] = [\n{}\n];",
-            metadata,
-            samples_i32.len(),
-            samples_i32
-                .iter()
-                .map(|s| format!("    {}", s))
+            "const {}: [{}; {}] = [\n{}\n];",
+            name,
+            element_type,
+            len,
+            values
+                .par_chunks(per_line)
+                .with_min_len(PARALLEL_CHUNK / per_line + 1)
+                .map(|line| format!("    {}", line.join(", ")))
                 .collect::<Vec<_>>()
                 .join(",\n")
         )
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::args::FloatFormat;
+
+    fn stereo_audio() -> ConvertedAudio {
+        ConvertedAudio {
+            samples: vec![0.0, 0.5, -0.5, 0.25, 1.0, -1.0],
+            sample_rate: 8000,
+            channels: 2,
+            format: OutputFormat::I16,
+        }
+    }
+
+    #[test]
+    fn test_values_per_line_alignment() {
+        let settings = OutputSettings {
+            values_per_line: 4,
+            ..Default::default()
+        };
+        let output = ArrayWriter::render(&stereo_audio(), &settings).unwrap();
+        assert!(output.contains(
+            "[i16; 6] = [\n         0,  16384, -16384,   8192,\n     32767, -32768\n];"
+        ));
+    }
+
+    #[test]
+    fn test_number_styles() {
+        let settings = OutputSettings {
+            hex_integers: true,
+            ..Default::default()
+        };
+        let output = ArrayWriter::render(&stereo_audio(), &settings).unwrap();
+        assert!(output.contains("    0x4000,\n    -0x4000,"));
+
+        let audio = ConvertedAudio {
+            format: OutputFormat::F32,
+            ..stereo_audio()
+        };
+        for (float_format, expected) in [
+            (FloatFormat::Fixed, "    0.250000,"),
+            (FloatFormat::Shortest, "    0.25,"),
+            (FloatFormat::Bits, "    f32::from_bits(0x3E800000),"),
+        ] {
+            let settings = OutputSettings {
+                float_format,
+                ..Default::default()
+            };
+            let output = ArrayWriter::render(&audio, &settings).unwrap();
+            assert!(output.contains(expected), "{:?}: {}", float_format, output);
+        }
+    }
+
+    #[test]
+    fn test_float_format_must_fit_target() {
+        let audio = ConvertedAudio {
+            format: OutputFormat::F32,
+            ..stereo_audio()
+        };
+        // Rust 没有十六进制浮点字面量
+        let hex = OutputSettings {
+            float_format: FloatFormat::HexFloat,
+            ..Default::default()
+        };
+        assert!(ArrayWriter::render(&audio, &hex).is_err());
+        // 整数格式不受浮点写法影响
+        assert!(!ArrayWriter::render(&stereo_audio(), &hex).unwrap().contains("p-"));
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("beep.h");
+        let path = path.to_str().unwrap();
+        let c_header = |float_format| OutputSettings {
+            float_format,
+            template: Some("builtin:c-header".to_string()),
+            ..Default::default()
+        };
+        ArrayWriter::write_with_settings(&audio, path, &c_header(FloatFormat::HexFloat)).unwrap();
+        assert!(std::fs::read_to_string(path).unwrap().contains("0x1p-2,"));
+        // 位模式写法只有 Rust 能编译
+        assert!(ArrayWriter::write_with_settings(&audio, path, &c_header(FloatFormat::Bits)).is_err());
+        let js = OutputSettings {
+            template: Some("builtin:js-float32array".to_string()),
+            ..c_header(FloatFormat::HexFloat)
+        };
+        assert!(ArrayWriter::write_with_settings(&audio, path, &js).is_err());
+    }
+
+    #[test]
+    fn test_render_template() {
+        let template = "// {{metadata}}\n\
+                        static const {{c_type}} {{NAME}}[{{length}}] = {\n\
+                        {{#chunks 4}}    {{chunk_values}}{{separator}}\n{{/chunks}}};\n\
+                        #define {{NAME}}_RATE {{sample_rate}}\n";
+        let output = ArrayWriter::render_template(&stereo_audio(), &OutputSettings::default(), template, "my-beep").unwrap();
+
+        assert!(output.starts_with("// {\"channels\":2,"));
+        assert!(output.contains(
+            "static const int16_t MY_BEEP[6] = {\n    0, 16384, -16384, 8192,\n    32767, -32768\n};\n"
+        ));
+        assert!(output.contains("#define MY_BEEP_RATE 8000"));
+    }
+
+    #[test]
+    fn test_render_features() {
+        use crate::audio::features::{FeatureSettings, Features};
+        use crate::cli::args::FeatureKind;
+
+        let features = Features {
+            data: vec![0.5, 1.0, 1.5, 2.0, 2.5, 3.0],
+            frames: 2,
+            bins: 3,
+            sample_rate: 16000,
+            settings: FeatureSettings::new(FeatureKind::LogMel),
+        };
+        let settings = OutputSettings {
+            float_format: FloatFormat::Shortest,
+            ..Default::default()
+        };
+        let output = ArrayWriter::render_features(&features, OutputFormat::F32, &settings).unwrap();
+
+        assert!(output.contains("\"shape\":[2,3]"));
+        assert!(output.contains("\"kind\":\"log-mel\""));
+        assert!(output.contains("const FEATURE_FRAMES: usize = 2;\nconst FEATURE_BINS: usize = 3;"));
+        assert!(output.contains(
+            "const FEATURES: [[f32; 3]; 2] = [\n    [0.5, 1.0, 1.5],\n    [2.0, 2.5, 3.0]\n];"
+        ));
+    }
+
+    #[test]
+    fn test_render_features_honors_settings() {
+        use crate::audio::features::{FeatureSettings, Features};
+        use crate::cli::args::FeatureKind;
+
+        let features = Features {
+            data: vec![0.5, 1.0],
+            frames: 1,
+            bins: 2,
+            sample_rate: 16000,
+            settings: FeatureSettings::new(FeatureKind::Mfcc),
+        };
+        let settings = OutputSettings {
+            float_format: FloatFormat::Shortest,
+            include_metadata: false,
+            name: Some("kws".to_string()),
+            ..Default::default()
+        };
+        let output = ArrayWriter::render_features(&features, OutputFormat::F64, &settings).unwrap();
+        assert!(!output.contains("特征元数据"));
+        assert!(output.contains("const KWS_FRAMES: usize = 1;\nconst KWS_BINS: usize = 2;"));
+        assert!(output.contains("const KWS_FEATURES: [[f64; 2]; 1]"));
+
+        // 不适用于特征矩阵的设置报错，而不是被忽略
+        let rejected = [
+            (OutputFormat::I16, OutputSettings::default()),
+            (
+                OutputFormat::F32,
+                OutputSettings { template: Some("builtin:c-header".to_string()), ..Default::default() },
+            ),
+            (
+                OutputFormat::F32,
+                OutputSettings { layout: ArrayLayout::PerChannel, ..Default::default() },
+            ),
+        ];
+        for (format, settings) in rejected {
+            assert!(ArrayWriter::render_features(&features, format, &settings).is_err());
+        }
+    }
+
+    #[test]
+    fn test_render_peaks() {
+        use crate::audio::peaks::{self, PeakSettings};
+
+        let settings = PeakSettings {
+            samples_per_pixel: Some(2),
+            rms: true,
+            bits: 8,
+            ..Default::default()
+        };
+        let peaks = peaks::compute(&stereo_audio().samples, 8000, 2, &settings).unwrap();
+        let output = ArrayWriter::render_peaks(&peaks);
+
+        assert!(output.contains("\"samples_per_pixel\":2"));
+        assert!(output.contains("const PEAKS_LENGTH: usize = 2;\nconst PEAKS_CHANNELS: usize = 2;"));
+        assert!(output.contains("const PEAKS: [i8; 8] = [\n    -64, 0, 32, 64,\n    127, 127, -128, -128\n];"));
+        assert!(output.contains("const PEAKS_RMS: [i8; 4] = [\n    45, 51,\n    127, 127\n];"));
+    }
+
+    fn render(layout: ArrayLayout) -> String {
+        let settings = OutputSettings {
+            layout,
+            ..Default::default()
+        };
+        ArrayWriter::render(&stereo_audio(), &settings).unwrap()
+    }
+
+    #[test]
+    fn test_length_constants() {
+        let output = render(ArrayLayout::Interleaved);
+        assert!(output.contains("const AUDIO_FRAMES: usize = 3;"));
+        assert!(output.contains("const AUDIO_CHANNELS: usize = 2;"));
+        assert!(output.contains("const AUDIO_LENGTH: usize = 6;"));
+        assert!(output.contains("\"layout\":\"interleaved\""));
+        assert!(output.contains("const AUDIO_SAMPLES: [i16; 6] = [\n    0,\n    16384,\n    -16384,"));
+    }
+
+    #[test]
+    fn test_identifier_name_and_metadata() {
+        let settings = OutputSettings {
+            layout: ArrayLayout::PerChannel,
+            name: Some("my-beep".to_string()),
+            include_metadata: false,
+            ..Default::default()
+        };
+        let output = ArrayWriter::render(&stereo_audio(), &settings).unwrap();
+        assert!(!output.contains("音频元数据"));
+        assert!(output.contains("const MY_BEEP_FRAMES: usize = 3;"));
+        assert!(output.contains("const MY_BEEP_LEFT: [i16; 3]"));
+        assert!(output.contains("const MY_BEEP_RIGHT: [i16; 3]"));
+    }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let samples: Vec<f32> = (0..PARALLEL_CHUNK * 3 + 7).map(|i| (i as f32 * 0.013).sin() * 1.2).collect();
+        let settings = OutputSettings {
+            values_per_line: 8,
+            ..Default::default()
+        };
+        let render_with = |threads: usize, format: OutputFormat| {
+            let audio = ConvertedAudio {
+                samples: samples.clone(),
+                sample_rate: 8000,
+                channels: 1,
+                format,
+            };
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| ArrayWriter::render(&audio, &settings).unwrap())
+        };
+        for format in [OutputFormat::F32, OutputFormat::I16, OutputFormat::I24Packed] {
+            assert_eq!(render_with(1, format), render_with(4, format));
+        }
+    }
+
+    #[test]
+    fn test_planar_layout() {
+        let output = render(ArrayLayout::Planar);
+        assert!(output.contains(
+            "[i16; 6] = [\n    0,\n    -16384,\n    32767,\n    16384,\n    8192,\n    -32768\n];"
+        ));
+    }
+
+    #[test]
+    fn test_per_channel_layout() {
+        let output = render(ArrayLayout::PerChannel);
+        assert!(output.contains("const AUDIO_LEFT: [i16; 3] = [\n    0,\n    -16384,\n    32767\n];"));
+        assert!(output.contains("const AUDIO_RIGHT: [i16; 3] = [\n    16384,\n    8192,\n    -32768\n];"));
+    }
+
+    #[test]
+    fn test_two_d_layout() {
+        let output = render(ArrayLayout::TwoD);
+        assert!(output.contains(
+            "const AUDIO_SAMPLES: [[i16; 2]; 3] = [\n    [0, 16384],\n    [-16384, 8192],\n    [32767, -32768]\n];"
+        ));
+    }
+}
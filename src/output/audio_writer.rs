@@ -0,0 +1,193 @@
+use crate::audio::converter::ConvertedAudio;
+use crate::cli::args::{OutputFormat, WavFormat};
+use crate::output::flac_encoder;
+use crate::output::sample_format::Sample;
+use hound::{SampleFormat, WavSpec, WavWriter};
+use std::path::Path;
+use anyhow::Result;
+
+/// 将转换结果写出为可播放的音频文件（WAV / FLAC）
+pub struct AudioWriter;
+
+impl AudioWriter {
+    /// 输出路径是否为音频文件（按扩展名判断）
+    pub fn is_audio_path(path: &str) -> bool {
+        matches!(Self::extension(path).as_deref(), Some("wav") | Some("flac"))
+    }
+
+    /// 根据扩展名选择 WAV 或 FLAC 输出
+    pub fn write_to_file<S: Sample>(audio: &ConvertedAudio<S>, output_path: &str, format: WavFormat) -> Result<()> {
+        match Self::extension(output_path).as_deref() {
+            Some("flac") => Self::write_flac(audio, output_path, format)?,
+            _ => Self::write_wav(audio, output_path, format)?,
+        }
+
+        println!("音频已写入: {}", output_path);
+        Ok(())
+    }
+
+    /// 未显式指定时，按数组格式选择最接近的音频格式
+    pub fn default_format(output_format: OutputFormat) -> WavFormat {
+        match output_format {
+            OutputFormat::F32 | OutputFormat::F64 => WavFormat::Float32,
+            OutputFormat::I8 | OutputFormat::U8 => WavFormat::Pcm8,
+            OutputFormat::I16 | OutputFormat::U16 | OutputFormat::Q15 => WavFormat::Pcm16,
+            OutputFormat::I24 | OutputFormat::I24Packed => WavFormat::Pcm24,
+            OutputFormat::I32 | OutputFormat::Q31 => WavFormat::Pcm32,
+        }
+    }
+
+    pub fn write_wav<S: Sample>(audio: &ConvertedAudio<S>, output_path: &str, format: WavFormat) -> Result<()> {
+        let spec = WavSpec {
+            channels: audio.channels as u16,
+            sample_rate: audio.sample_rate,
+            bits_per_sample: format.bits_per_sample() as u16,
+            sample_format: match format {
+                WavFormat::Float32 => SampleFormat::Float,
+                _ => SampleFormat::Int,
+            },
+        };
+
+        let mut writer = WavWriter::create(output_path, spec)?;
+        match format {
+            WavFormat::Float32 => {
+                for &sample in &audio.samples {
+                    writer.write_sample(sample.to_f32())?;
+                }
+            }
+            WavFormat::Pcm8 => {
+                // hound 会把 i8 转换为 WAV 规定的无符号 8 位格式
+                for &sample in &audio.samples {
+                    writer.write_sample(sample.quantize(8) as i8)?;
+                }
+            }
+            WavFormat::Pcm16 => {
+                for &sample in &audio.samples {
+                    writer.write_sample(sample.quantize(16) as i16)?;
+                }
+            }
+            WavFormat::Pcm24 | WavFormat::Pcm32 => {
+                let bits = format.bits_per_sample();
+                for &sample in &audio.samples {
+                    writer.write_sample(sample.quantize(bits))?;
+                }
+            }
+        }
+
+        writer.finalize()?;
+        Ok(())
+    }
+
+    pub fn write_flac<S: Sample>(audio: &ConvertedAudio<S>, output_path: &str, format: WavFormat) -> Result<()> {
+        let bits = match format {
+            WavFormat::Pcm8 | WavFormat::Pcm16 | WavFormat::Pcm24 => format.bits_per_sample(),
+            _ => anyhow::bail!("FLAC 仅支持 8/16/24 位整数格式，当前为 {:?}", format),
+        };
+        if audio.channels == 0 || audio.channels > 8 {
+            anyhow::bail!("FLAC 仅支持 1-8 个声道，当前为 {}", audio.channels);
+        }
+
+        let samples = S::quantize_all(&audio.samples, bits);
+        let bytes = flac_encoder::encode(&samples, audio.sample_rate, audio.channels, bits);
+        std::fs::write(output_path, bytes)?;
+        Ok(())
+    }
+
+    fn extension(path: &str) -> Option<String> {
+        Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::decoder::AudioDecoder;
+    use crate::output::sample_format::quantize;
+    use tempfile::TempDir;
+
+    fn test_audio(channels: u32) -> ConvertedAudio {
+        let samples = (0..4410 * channels as usize)
+            .map(|i| ((i / channels as usize) as f32 * 0.05).sin() * 0.5)
+            .collect();
+        ConvertedAudio {
+            samples,
+            sample_rate: 44100,
+            channels,
+            format: OutputFormat::F32,
+        }
+    }
+
+    #[test]
+    fn test_is_audio_path() {
+        assert!(AudioWriter::is_audio_path("out.wav"));
+        assert!(AudioWriter::is_audio_path("out.FLAC"));
+        assert!(!AudioWriter::is_audio_path("out.rs"));
+        assert!(!AudioWriter::is_audio_path("out"));
+    }
+
+    #[test]
+    fn test_wav_formats_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let audio = test_audio(2);
+
+        for (format, bits, tolerance) in [
+            (WavFormat::Pcm8, 8, 4e-3),
+            (WavFormat::Pcm16, 16, 2e-5),
+            (WavFormat::Pcm24, 24, 1e-6),
+            (WavFormat::Pcm32, 32, 1e-6),
+            (WavFormat::Float32, 32, 0.0),
+        ] {
+            let path = temp_dir.path().join(format!("{:?}.wav", format));
+            let path = path.to_str().unwrap();
+            AudioWriter::write_to_file(&audio, path, format).unwrap();
+
+            let reader = hound::WavReader::open(path).unwrap();
+            assert_eq!(reader.spec().bits_per_sample, bits);
+            assert_eq!(reader.spec().channels, 2);
+
+            let mut decoder = AudioDecoder::new();
+            decoder.decode_file(path).unwrap();
+            assert_eq!(decoder.get_samples().len(), audio.samples.len());
+            for (a, b) in decoder.get_samples().iter().zip(&audio.samples) {
+                assert!((a - b).abs() <= tolerance + 1e-7, "{:?}: {} vs {}", format, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_flac_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("out.flac");
+        let path = path.to_str().unwrap();
+
+        let audio = test_audio(2);
+        AudioWriter::write_to_file(&audio, path, WavFormat::Pcm16).unwrap();
+
+        // 正弦信号经预测编码后应明显小于原始 PCM
+        let size = std::fs::metadata(path).unwrap().len() as usize;
+        assert!(size < audio.samples.len() * 2);
+
+        let mut decoder = AudioDecoder::new();
+        decoder.decode_file(path).unwrap();
+        assert_eq!(decoder.get_sample_rate(), 44100);
+        assert_eq!(decoder.get_channels(), 2);
+
+        let expected: Vec<f32> = audio
+            .samples
+            .iter()
+            .map(|&s| quantize(s, 16) as f32 / 32768.0)
+            .collect();
+        assert_eq!(decoder.get_samples(), expected.as_slice());
+    }
+
+    #[test]
+    fn test_flac_rejects_float() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("out.flac");
+        let result = AudioWriter::write_flac(&test_audio(1), path.to_str().unwrap(), WavFormat::Float32);
+        assert!(result.is_err());
+    }
+}
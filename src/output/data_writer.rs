@@ -0,0 +1,330 @@
+use crate::audio::converter::ConvertedAudio;
+use crate::audio::features::{self, Features};
+use crate::audio::peaks::Peaks;
+use crate::cli::args::{Endianness, OutputFormat};
+use crate::config::settings::OutputSettings;
+use crate::output::sample_format::{self, Sample};
+use serde_json::json;
+use std::path::Path;
+use anyhow::Result;
+
+/// 将转换结果写出为数据文件（JSON / CSV / NPY / raw PCM）
+pub struct DataWriter;
+
+impl DataWriter {
+    /// 输出路径是否为数据文件（按扩展名判断）
+    pub fn is_data_path(path: &str) -> bool {
+        matches!(
+            Self::extension(path).as_deref(),
+            Some("json") | Some("csv") | Some("npy") | Some("raw") | Some("pcm")
+        )
+    }
+
+    pub fn write_to_file<S: Sample>(audio: &ConvertedAudio<S>, output_path: &str, settings: &OutputSettings) -> Result<()> {
+        let bytes = match Self::extension(output_path).as_deref() {
+            Some("json") => Self::to_json(audio)?.into_bytes(),
+            Some("csv") => Self::to_csv(audio, settings.csv_timestamps).into_bytes(),
+            Some("npy") => Self::to_npy(audio, settings.endianness),
+            _ => Self::to_raw(audio, settings.endianness),
+        };
+
+        std::fs::write(output_path, bytes)?;
+        println!("数据已写入: {}", output_path);
+        Ok(())
+    }
+
+    /// 特征矩阵：JSON 为元数据 + 二维数组，CSV 每帧一行，NPY/raw 为 (帧数, 维数) 矩阵
+    ///
+    /// `format` 为 f32 或 f64，决定 NPY/raw 的元素类型；`include_metadata` 关闭时 JSON 只包含特征数组。
+    pub fn write_features(features: &Features, format: OutputFormat, output_path: &str, settings: &OutputSettings) -> Result<()> {
+        features::check_output(format, settings)?;
+
+        // 复用样本写出逻辑：每一帧视为一个「多声道」帧
+        let matrix = ConvertedAudio {
+            samples: features.data.clone(),
+            sample_rate: features.sample_rate,
+            channels: features.bins as u32,
+            format,
+        };
+
+        let bytes = match Self::extension(output_path).as_deref() {
+            Some("json") => {
+                let rows: Vec<&[f32]> = (0..features.frames).map(|f| features.row(f)).collect();
+                let mut document = json!({ "features": rows });
+                if settings.include_metadata {
+                    document["metadata"] = features.metadata();
+                }
+                serde_json::to_string_pretty(&document)?.into_bytes()
+            }
+            Some("csv") => Self::to_csv(&matrix, false)
+                .replacen("channel_", "bin_", features.bins)
+                .into_bytes(),
+            Some("npy") => Self::to_npy(&matrix, settings.endianness),
+            _ => Self::to_raw(&matrix, settings.endianness),
+        };
+
+        std::fs::write(output_path, bytes)?;
+        println!("特征已写入: {}", output_path);
+        Ok(())
+    }
+
+    /// audiowaveform 风格的 JSON：头部字段 + 交错的 min/max 数据，开启 RMS 时附加 `rms` 数组
+    pub fn write_peaks(peaks: &Peaks, output_path: &str) -> Result<()> {
+        std::fs::write(output_path, Self::peaks_to_json(peaks)?)?;
+        println!("峰值数据已写入: {}", output_path);
+        Ok(())
+    }
+
+    pub fn peaks_to_json(peaks: &Peaks) -> Result<String> {
+        let mut document = peaks.metadata();
+        document["data"] = json!(peaks.data());
+        if let Some(rms) = peaks.rms_data() {
+            document["rms"] = json!(rms);
+        }
+        Ok(serde_json::to_string(&document)?)
+    }
+
+    /// 元数据对象 + 交错排列的样本数组
+    pub fn to_json<S: Sample>(audio: &ConvertedAudio<S>) -> Result<String> {
+        let channels = audio.channels.max(1) as usize;
+        let samples: Vec<serde_json::Value> = match audio.format {
+            OutputFormat::F32 => audio.samples.iter().map(|&s| json!(s.to_f32())).collect(),
+            OutputFormat::F64 => audio.samples.iter().map(|&s| json!(s.to_f32() as f64)).collect(),
+            format => audio.samples
+                .iter()
+                .filter_map(|&s| sample_format::encode_sample(s, format))
+                .map(|v| json!(v))
+                .collect(),
+        };
+
+        let document = json!({
+            "metadata": {
+                "sample_rate": audio.sample_rate,
+                "channels": audio.channels,
+                "frames": audio.samples.len() / channels,
+                "length": audio.samples.len(),
+                "format": audio.format.name(),
+                "layout": "interleaved"
+            },
+            "samples": samples
+        });
+
+        Ok(serde_json::to_string_pretty(&document)?)
+    }
+
+    /// 每帧一行、每个声道一列，可选在第一列输出时间戳（秒）
+    pub fn to_csv<S: Sample>(audio: &ConvertedAudio<S>, timestamps: bool) -> String {
+        let channels = audio.channels.max(1) as usize;
+        let mut header: Vec<String> = (0..channels).map(|ch| format!("channel_{}", ch)).collect();
+        if timestamps {
+            header.insert(0, "time".to_string());
+        }
+
+        let mut csv = header.join(",");
+        csv.push('\n');
+
+        for (frame, samples) in audio.samples.chunks_exact(channels).enumerate() {
+            let mut row: Vec<String> = samples
+                .iter()
+                .map(|&s| match sample_format::encode_sample(s, audio.format) {
+                    Some(value) => value.to_string(),
+                    None => s.to_f32().to_string(),
+                })
+                .collect();
+            if timestamps {
+                row.insert(0, format!("{:.6}", frame as f64 / audio.sample_rate as f64));
+            }
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    /// NumPy `.npy`（版本 1.0），形状为 (帧数, 声道数)
+    pub fn to_npy<S: Sample>(audio: &ConvertedAudio<S>, endianness: Endianness) -> Vec<u8> {
+        let channels = audio.channels.max(1) as usize;
+        let frames = audio.samples.len() / channels;
+        let header = format!(
+            "{{'descr': '{}', 'fortran_order': False, 'shape': ({}, {}), }}",
+            Self::npy_descr(audio.format, endianness),
+            frames,
+            channels
+        );
+
+        // 魔数 + 版本 + 头长度共 10 字节，头部以换行结尾并补齐到 64 字节对齐
+        let padding = 64 - (10 + header.len() + 1) % 64;
+        let header = format!("{}{}\n", header, " ".repeat(padding % 64));
+
+        let mut npy = Vec::with_capacity(10 + header.len() + audio.samples.len() * 4);
+        npy.extend_from_slice(b"\x93NUMPY");
+        npy.extend_from_slice(&[1, 0]);
+        npy.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        npy.extend_from_slice(header.as_bytes());
+
+        // NumPy 没有 24 位整数类型，打包格式按 i4 写出
+        let format = match audio.format {
+            OutputFormat::I24Packed => OutputFormat::I24,
+            format => format,
+        };
+        let audio = ConvertedAudio {
+            samples: audio.samples[..frames * channels].to_vec(),
+            sample_rate: audio.sample_rate,
+            channels: audio.channels,
+            format,
+        };
+        npy.extend_from_slice(&Self::to_raw(&audio, endianness));
+        npy
+    }
+
+    /// 无文件头的 PCM 数据，按样本格式和字节序编码
+    pub fn to_raw<S: Sample>(audio: &ConvertedAudio<S>, endianness: Endianness) -> Vec<u8> {
+        let format = audio.format;
+        let width = format.bytes_per_sample();
+        let mut raw = Vec::with_capacity(audio.samples.len() * width);
+
+        for &sample in &audio.samples {
+            let mut bytes = match format {
+                OutputFormat::F32 => sample.to_f32().to_le_bytes().to_vec(),
+                OutputFormat::F64 => (sample.to_f32() as f64).to_le_bytes().to_vec(),
+                _ => {
+                    let value = sample_format::encode_sample(sample, format).unwrap_or(0);
+                    value.to_le_bytes()[..width].to_vec()
+                }
+            };
+            if endianness == Endianness::Big {
+                bytes.reverse();
+            }
+            raw.extend_from_slice(&bytes);
+        }
+
+        raw
+    }
+
+    fn npy_descr(format: OutputFormat, endianness: Endianness) -> String {
+        let kind = match format {
+            OutputFormat::F32 | OutputFormat::F64 => 'f',
+            OutputFormat::U8 | OutputFormat::U16 => 'u',
+            _ => 'i',
+        };
+        let width = match format {
+            OutputFormat::I24Packed => 4,
+            format => format.bytes_per_sample(),
+        };
+        // 单字节类型没有字节序
+        let order = match (width, endianness) {
+            (1, _) => '|',
+            (_, Endianness::Little) => '<',
+            (_, Endianness::Big) => '>',
+        };
+        format!("{}{}{}", order, kind, width)
+    }
+
+    fn extension(path: &str) -> Option<String> {
+        Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_audio(format: OutputFormat) -> ConvertedAudio {
+        ConvertedAudio {
+            samples: vec![0.0, 0.5, -0.5, -1.0],
+            sample_rate: 4,
+            channels: 2,
+            format,
+        }
+    }
+
+    #[test]
+    fn test_is_data_path() {
+        assert!(DataWriter::is_data_path("out.json"));
+        assert!(DataWriter::is_data_path("out.NPY"));
+        assert!(DataWriter::is_data_path("out.pcm"));
+        assert!(!DataWriter::is_data_path("out.rs"));
+        assert!(!DataWriter::is_data_path("out.wav"));
+    }
+
+    #[test]
+    fn test_json() {
+        let json = DataWriter::to_json(&test_audio(OutputFormat::I16)).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["metadata"]["frames"], 2);
+        assert_eq!(value["metadata"]["format"], "i16");
+        assert_eq!(value["samples"], json!([0, 16384, -16384, -32768]));
+
+        let json = DataWriter::to_json(&test_audio(OutputFormat::F32)).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["samples"], json!([0.0, 0.5, -0.5, -1.0]));
+    }
+
+    #[test]
+    fn test_peaks_json() {
+        use crate::audio::peaks::{self, PeakSettings};
+
+        let settings = PeakSettings {
+            points: Some(1),
+            ..Default::default()
+        };
+        let peaks = peaks::compute(&test_audio(OutputFormat::F32).samples, 4, 2, &settings).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&DataWriter::peaks_to_json(&peaks).unwrap()).unwrap();
+
+        assert_eq!(value["version"], 2);
+        assert_eq!(value["bits"], 16);
+        assert_eq!(value["samples_per_pixel"], 2);
+        assert_eq!(value["length"], 1);
+        assert_eq!(value["data"], json!([-16384, 0, -32768, 16384]));
+        assert!(value.get("rms").is_none());
+    }
+
+    #[test]
+    fn test_csv() {
+        let csv = DataWriter::to_csv(&test_audio(OutputFormat::U8), true);
+        assert_eq!(csv, "time,channel_0,channel_1\n0.000000,128,192\n0.250000,64,0\n");
+
+        let csv = DataWriter::to_csv(&test_audio(OutputFormat::F32), false);
+        assert_eq!(csv, "channel_0,channel_1\n0,0.5\n-0.5,-1\n");
+    }
+
+    #[test]
+    fn test_raw_endianness() {
+        let audio = test_audio(OutputFormat::I16);
+        assert_eq!(
+            DataWriter::to_raw(&audio, Endianness::Little),
+            vec![0x00, 0x00, 0x00, 0x40, 0x00, 0xC0, 0x00, 0x80]
+        );
+        assert_eq!(
+            DataWriter::to_raw(&audio, Endianness::Big),
+            vec![0x00, 0x00, 0x40, 0x00, 0xC0, 0x00, 0x80, 0x00]
+        );
+
+        let audio = test_audio(OutputFormat::I24Packed);
+        assert_eq!(DataWriter::to_raw(&audio, Endianness::Little).len(), 12);
+        assert_eq!(&DataWriter::to_raw(&audio, Endianness::Big)[3..6], &[0x40, 0x00, 0x00]);
+
+        let audio = test_audio(OutputFormat::F32);
+        assert_eq!(&DataWriter::to_raw(&audio, Endianness::Big)[4..8], &0.5f32.to_be_bytes());
+    }
+
+    #[test]
+    fn test_npy_header() {
+        let npy = DataWriter::to_npy(&test_audio(OutputFormat::I16), Endianness::Little);
+        assert_eq!(&npy[..8], b"\x93NUMPY\x01\x00");
+
+        let header_len = u16::from_le_bytes([npy[8], npy[9]]) as usize;
+        assert_eq!((10 + header_len) % 64, 0);
+
+        let header = std::str::from_utf8(&npy[10..10 + header_len]).unwrap();
+        assert!(header.starts_with("{'descr': '<i2', 'fortran_order': False, 'shape': (2, 2), }"));
+        assert!(header.ends_with('\n'));
+        assert_eq!(npy.len(), 10 + header_len + 8);
+
+        let npy = DataWriter::to_npy(&test_audio(OutputFormat::U8), Endianness::Big);
+        assert!(npy.windows(5).any(|w| w == b"'|u1'"));
+    }
+}
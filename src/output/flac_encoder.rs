@@ -0,0 +1,315 @@
+//! 简单的 FLAC 编码器
+//!
+//! 使用固定块大小，每个子帧在 CONSTANT、FIXED（0-4 阶预测 + Rice 编码）
+//! 和 VERBATIM 中选择最小的编码方式。
+
+/// 每帧的样本数（每声道）
+const BLOCK_SIZE: usize = 4096;
+/// 4 位 Rice 参数的最大值（15 为转义码）
+const MAX_RICE_PARAM: u32 = 14;
+
+/// 将交错排列的整数样本编码为完整的 FLAC 文件
+pub fn encode(samples: &[i32], sample_rate: u32, channels: u32, bits_per_sample: u32) -> Vec<u8> {
+    let channel_count = channels.max(1) as usize;
+    let total_frames = samples.len() / channel_count;
+    let block_size = BLOCK_SIZE.min(total_frames.max(1));
+
+    let mut out = Vec::new();
+    out.extend_from_slice(b"fLaC");
+    // STREAMINFO 中的块大小不得小于 16（最后一帧除外）
+    write_stream_info(&mut out, block_size.max(16), sample_rate, channel_count, bits_per_sample, total_frames);
+
+    let mut channel_buf = vec![0i32; block_size];
+    for (frame_index, block) in samples[..total_frames * channel_count]
+        .chunks(block_size * channel_count)
+        .enumerate()
+    {
+        let frames_in_block = block.len() / channel_count;
+        let mut writer = BitWriter::new();
+        write_frame_header(&mut writer, frame_index as u64, frames_in_block, channel_count, bits_per_sample);
+
+        for ch in 0..channel_count {
+            for (i, value) in channel_buf[..frames_in_block].iter_mut().enumerate() {
+                *value = block[i * channel_count + ch];
+            }
+            write_subframe(&mut writer, &channel_buf[..frames_in_block], bits_per_sample);
+        }
+
+        writer.align();
+        let crc = crc16(&writer.bytes);
+        writer.write(crc as u64, 16);
+        out.extend_from_slice(&writer.bytes);
+    }
+
+    out
+}
+
+fn write_stream_info(
+    out: &mut Vec<u8>,
+    block_size: usize,
+    sample_rate: u32,
+    channels: usize,
+    bits_per_sample: u32,
+    total_frames: usize,
+) {
+    let mut writer = BitWriter::new();
+    // 元数据块头：最后一个块 + STREAMINFO，长度 34 字节
+    writer.write(1, 1);
+    writer.write(0, 7);
+    writer.write(34, 24);
+
+    writer.write(block_size as u64, 16);
+    writer.write(block_size as u64, 16);
+    writer.write(0, 24); // 最小帧长度未知
+    writer.write(0, 24); // 最大帧长度未知
+    writer.write(sample_rate as u64, 20);
+    writer.write((channels - 1) as u64, 3);
+    writer.write((bits_per_sample - 1) as u64, 5);
+    writer.write(total_frames as u64, 36);
+    writer.write(0, 64); // MD5 未计算
+    writer.write(0, 64);
+
+    out.extend_from_slice(&writer.bytes);
+}
+
+fn write_frame_header(
+    writer: &mut BitWriter,
+    frame_index: u64,
+    block_size: usize,
+    channels: usize,
+    bits_per_sample: u32,
+) {
+    let sample_size_code = match bits_per_sample {
+        8 => 0b001,
+        12 => 0b010,
+        16 => 0b100,
+        20 => 0b101,
+        24 => 0b110,
+        _ => 0b000, // 从 STREAMINFO 读取
+    };
+
+    writer.write(0b11_1111_1111_1110, 14); // 同步码
+    writer.write(0, 1);
+    writer.write(0, 1); // 固定块大小
+    writer.write(0b0111, 4); // 块大小在帧头末尾以 16 位给出
+    writer.write(0b0000, 4); // 采样率从 STREAMINFO 读取
+    writer.write((channels - 1) as u64, 4); // 各声道独立编码
+    writer.write(sample_size_code, 3);
+    writer.write(0, 1);
+    write_utf8_number(writer, frame_index);
+    writer.write((block_size - 1) as u64, 16);
+
+    let crc = crc8(&writer.bytes);
+    writer.write(crc as u64, 8);
+}
+
+fn write_subframe(writer: &mut BitWriter, samples: &[i32], bits_per_sample: u32) {
+    if samples.iter().all(|&s| s == samples[0]) {
+        writer.write(0, 1);
+        writer.write(0b000000, 6);
+        writer.write(0, 1);
+        writer.write_signed(samples[0] as i64, bits_per_sample);
+        return;
+    }
+
+    // 选择残差绝对值之和最小的固定预测阶数
+    let max_order = 4.min(samples.len());
+    let (order, residuals) = (0..=max_order)
+        .map(|order| (order, fixed_residuals(samples, order)))
+        .min_by_key(|(_, residuals)| residuals.iter().map(|r| r.unsigned_abs()).sum::<u64>())
+        .unwrap();
+
+    let (rice_param, residual_bits) = best_rice_parameter(&residuals);
+    let fixed_bits = 8 + order as u64 * bits_per_sample as u64 + 10 + residual_bits;
+    let verbatim_bits = 8 + samples.len() as u64 * bits_per_sample as u64;
+
+    if fixed_bits >= verbatim_bits {
+        writer.write(0, 1);
+        writer.write(0b000001, 6);
+        writer.write(0, 1);
+        for &sample in samples {
+            writer.write_signed(sample as i64, bits_per_sample);
+        }
+        return;
+    }
+
+    writer.write(0, 1);
+    writer.write(0b001000 | order as u64, 6);
+    writer.write(0, 1);
+    for &sample in &samples[..order] {
+        writer.write_signed(sample as i64, bits_per_sample);
+    }
+
+    writer.write(0b00, 2); // 4 位 Rice 参数
+    writer.write(0, 4); // 分区阶数 0
+    writer.write(rice_param as u64, 4);
+    for &residual in &residuals {
+        let folded = zigzag(residual);
+        let quotient = folded >> rice_param;
+        writer.write_unary(quotient);
+        writer.write(folded & ((1u64 << rice_param) - 1), rice_param);
+    }
+}
+
+fn fixed_residuals(samples: &[i32], order: usize) -> Vec<i64> {
+    let s = |i: usize| samples[i] as i64;
+    (order..samples.len())
+        .map(|i| match order {
+            0 => s(i),
+            1 => s(i) - s(i - 1),
+            2 => s(i) - 2 * s(i - 1) + s(i - 2),
+            3 => s(i) - 3 * s(i - 1) + 3 * s(i - 2) - s(i - 3),
+            _ => s(i) - 4 * s(i - 1) + 6 * s(i - 2) - 4 * s(i - 3) + s(i - 4),
+        })
+        .collect()
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// 返回编码位数最少的 Rice 参数及对应的残差总位数
+fn best_rice_parameter(residuals: &[i64]) -> (u32, u64) {
+    let folded: Vec<u64> = residuals.iter().map(|&r| zigzag(r)).collect();
+    (0..=MAX_RICE_PARAM)
+        .map(|k| {
+            let bits = folded
+                .iter()
+                .map(|&u| (u >> k) + 1 + k as u64)
+                .sum::<u64>();
+            (k, bits)
+        })
+        .min_by_key(|&(_, bits)| bits)
+        .unwrap()
+}
+
+/// FLAC 帧号使用类 UTF-8 的变长编码
+fn write_utf8_number(writer: &mut BitWriter, value: u64) {
+    if value < 0x80 {
+        writer.write(value, 8);
+        return;
+    }
+
+    let mut continuation = Vec::new();
+    let mut remaining = value;
+    let mut first_capacity = 6u32; // 首字节可容纳的有效位数
+    loop {
+        continuation.push(0x80 | (remaining & 0x3F));
+        remaining >>= 6;
+        first_capacity -= 1;
+        if remaining < (1 << first_capacity) {
+            break;
+        }
+    }
+
+    let byte_count = continuation.len() + 1;
+    let prefix = (0xFF00u64 >> byte_count) & 0xFF;
+    writer.write(prefix | remaining, 8);
+    for byte in continuation.iter().rev() {
+        writer.write(*byte, 8);
+    }
+}
+
+fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for &byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 { (crc << 1) ^ 0x07 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x8005 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    accumulator: u64,
+    pending_bits: u32,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            accumulator: 0,
+            pending_bits: 0,
+        }
+    }
+
+    fn write(&mut self, value: u64, bits: u32) {
+        for i in (0..bits).rev() {
+            self.accumulator = (self.accumulator << 1) | ((value >> i) & 1);
+            self.pending_bits += 1;
+            if self.pending_bits == 8 {
+                self.bytes.push(self.accumulator as u8);
+                self.accumulator = 0;
+                self.pending_bits = 0;
+            }
+        }
+    }
+
+    fn write_signed(&mut self, value: i64, bits: u32) {
+        let mask = if bits == 64 { u64::MAX } else { (1u64 << bits) - 1 };
+        self.write(value as u64 & mask, bits);
+    }
+
+    fn write_unary(&mut self, zeros: u64) {
+        for _ in 0..zeros {
+            self.write(0, 1);
+        }
+        self.write(1, 1);
+    }
+
+    fn align(&mut self) {
+        if self.pending_bits > 0 {
+            self.write(0, 8 - self.pending_bits);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf8_frame_numbers() {
+        let encode_number = |value: u64| {
+            let mut writer = BitWriter::new();
+            write_utf8_number(&mut writer, value);
+            writer.bytes
+        };
+
+        assert_eq!(encode_number(0x00), vec![0x00]);
+        assert_eq!(encode_number(0x7F), vec![0x7F]);
+        assert_eq!(encode_number(0x80), vec![0xC2, 0x80]);
+        assert_eq!(encode_number(0x7FF), vec![0xDF, 0xBF]);
+        assert_eq!(encode_number(0x800), vec![0xE0, 0xA0, 0x80]);
+    }
+
+    #[test]
+    fn test_crc() {
+        assert_eq!(crc8(b"123456789"), 0xF4);
+        assert_eq!(crc16(b"123456789"), 0xFEE8);
+    }
+
+    #[test]
+    fn test_stream_header() {
+        let bytes = encode(&[0, 1, 2, 3], 44100, 2, 16);
+        assert_eq!(&bytes[..4], b"fLaC");
+        // 最后一个元数据块标志 + STREAMINFO 类型
+        assert_eq!(bytes[4], 0x80);
+        // 第一帧的同步码
+        assert_eq!(&bytes[42..44], &[0xFF, 0xF8]);
+    }
+}
@@ -0,0 +1,240 @@
+//! 波形图和频谱图渲染
+//!
+//! 波形图每个声道占一条高度相同的轨道，每列像素绘制该列对应样本的 min/max 竖线；
+//! 频谱图使用 STFT 幅度谱，按 dB 映射到颜色，低频在下。
+
+use crate::audio::features::{self, FeatureSettings};
+use crate::cli::args::FeatureKind;
+use crate::output::png_encoder;
+use anyhow::Result;
+
+/// 频谱图显示的动态范围 (dB)，低于最大值该范围的能量显示为最暗色
+const SPECTROGRAM_RANGE_DB: f64 = 80.0;
+
+/// 频谱图调色板：黑 → 紫 → 红 → 橙 → 浅黄
+const SPECTROGRAM_PALETTE: [Rgb; 5] = [
+    Rgb(0, 0, 4),
+    Rgb(87, 16, 110),
+    Rgb(188, 55, 84),
+    Rgb(249, 142, 9),
+    Rgb(252, 255, 164),
+];
+
+/// 8 位 RGB 颜色
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    /// 解析 `#RRGGBB` 或 `RRGGBB` 形式的颜色
+    pub fn parse(text: &str) -> Result<Self> {
+        let hex = text.trim().trim_start_matches('#');
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            anyhow::bail!("无效的颜色: {}（应为 #RRGGBB）", text);
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+        Ok(Rgb(channel(0), channel(2), channel(4)))
+    }
+
+    /// 按比例 t (0.0 - 1.0) 在两种颜色之间插值
+    fn mix(self, other: Rgb, t: f64) -> Rgb {
+        let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Rgb(lerp(self.0, other.0), lerp(self.1, other.1), lerp(self.2, other.2))
+    }
+}
+
+/// 波形图的尺寸和颜色
+#[derive(Debug, Clone)]
+pub struct WaveformStyle {
+    pub width: u32,
+    /// 每个声道轨道的高度
+    pub channel_height: u32,
+    pub background: Rgb,
+    pub color: Rgb,
+}
+
+/// RGB 像素画布
+#[derive(Debug, Clone)]
+pub struct Canvas {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Canvas {
+    pub fn new(width: u32, height: u32, background: Rgb) -> Self {
+        let pixels = [background.0, background.1, background.2].repeat(width as usize * height as usize);
+        Self { width, height, pixels }
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> Rgb {
+        let i = (y as usize * self.width as usize + x as usize) * 3;
+        Rgb(self.pixels[i], self.pixels[i + 1], self.pixels[i + 2])
+    }
+
+    pub fn set(&mut self, x: u32, y: u32, color: Rgb) {
+        if x < self.width && y < self.height {
+            let i = (y as usize * self.width as usize + x as usize) * 3;
+            self.pixels[i..i + 3].copy_from_slice(&[color.0, color.1, color.2]);
+        }
+    }
+
+    pub fn to_png(&self) -> Vec<u8> {
+        png_encoder::encode_rgb(&self.pixels, self.width, self.height)
+    }
+}
+
+/// 绘制波形图，图像高度为 `channel_height × 声道数`
+pub fn render_waveform(samples: &[f32], channels: u32, style: &WaveformStyle) -> Result<Canvas> {
+    validate_size(style.width, style.channel_height)?;
+    let channel_count = channels.max(1) as usize;
+    let mut canvas = Canvas::new(style.width, style.channel_height * channel_count as u32, style.background);
+
+    let frames = samples.len() / channel_count;
+    let axis = style.background.mix(style.color, 0.35);
+    let half = (style.channel_height - 1) as f64 / 2.0;
+
+    for ch in 0..channel_count {
+        let top = ch as u32 * style.channel_height;
+        let to_y = |value: f32| top + ((1.0 - value.clamp(-1.0, 1.0) as f64) * half).round() as u32;
+
+        for x in 0..style.width {
+            canvas.set(x, to_y(0.0), axis);
+        }
+        // 每列覆盖 [x·帧数/宽度, (x+1)·帧数/宽度) 的帧，音频比图像窄时每列至少取一帧
+        for x in 0..style.width as usize {
+            let start = x * frames / style.width as usize;
+            let end = ((x + 1) * frames / style.width as usize).max(start + 1).min(frames);
+            if start >= end {
+                continue;
+            }
+            let column = samples[start * channel_count..end * channel_count]
+                .iter()
+                .skip(ch)
+                .step_by(channel_count);
+            let (low, high) = column.fold((f32::INFINITY, f32::NEG_INFINITY), |(low, high), &s| {
+                (low.min(s), high.max(s))
+            });
+            for y in to_y(high)..=to_y(low) {
+                canvas.set(x as u32, y, style.color);
+            }
+        }
+    }
+
+    Ok(canvas)
+}
+
+/// 绘制频谱图：横轴为帧，纵轴为频率（0 到奈奎斯特频率），多声道先混合为单声道
+pub fn render_spectrogram(
+    samples: &[f32],
+    sample_rate: u32,
+    channels: u32,
+    width: u32,
+    height: u32,
+    settings: &FeatureSettings,
+) -> Result<Canvas> {
+    validate_size(width, height)?;
+    let settings = FeatureSettings {
+        kind: FeatureKind::Stft,
+        ..settings.clone()
+    };
+    let spectrum = features::extract(samples, sample_rate, channels, &settings)?;
+
+    let decibels: Vec<f64> = spectrum
+        .data
+        .iter()
+        .map(|&m| 20.0 * (m as f64).max(1e-10).log10())
+        .collect();
+    let max_db = decibels.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    // 最近邻缩放到目标尺寸
+    let mut canvas = Canvas::new(width, height, SPECTROGRAM_PALETTE[0]);
+    for x in 0..width {
+        let frame = x as usize * spectrum.frames / width as usize;
+        for y in 0..height {
+            let bin = (height - 1 - y) as usize * spectrum.bins / height as usize;
+            let db = decibels[frame * spectrum.bins + bin];
+            let level = 1.0 - ((max_db - db) / SPECTROGRAM_RANGE_DB).clamp(0.0, 1.0);
+            canvas.set(x, y, palette(level));
+        }
+    }
+
+    Ok(canvas)
+}
+
+fn palette(level: f64) -> Rgb {
+    let position = level.clamp(0.0, 1.0) * (SPECTROGRAM_PALETTE.len() - 1) as f64;
+    let index = (position.floor() as usize).min(SPECTROGRAM_PALETTE.len() - 2);
+    SPECTROGRAM_PALETTE[index].mix(SPECTROGRAM_PALETTE[index + 1], position - index as f64)
+}
+
+fn validate_size(width: u32, height: u32) -> Result<()> {
+    if width == 0 || height == 0 {
+        anyhow::bail!("图像尺寸必须大于 0，当前为 {}x{}", width, height);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Rgb = Rgb(0, 0, 0);
+    const WHITE: Rgb = Rgb(255, 255, 255);
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(Rgb::parse("#1E90FF").unwrap(), Rgb(0x1E, 0x90, 0xFF));
+        assert_eq!(Rgb::parse("00ff00").unwrap(), Rgb(0, 255, 0));
+        assert!(Rgb::parse("#12345").is_err());
+        assert!(Rgb::parse("#GGGGGG").is_err());
+    }
+
+    #[test]
+    fn test_waveform_tracks() {
+        // 左声道满幅方波，右声道静音
+        let samples: Vec<f32> = (0..64)
+            .flat_map(|i| [if i % 2 == 0 { 1.0 } else { -1.0 }, 0.0])
+            .collect();
+        let style = WaveformStyle {
+            width: 8,
+            channel_height: 9,
+            background: BLACK,
+            color: WHITE,
+        };
+        let canvas = render_waveform(&samples, 2, &style).unwrap();
+
+        assert_eq!((canvas.width, canvas.height), (8, 18));
+        // 左声道每列从顶到底填满
+        assert!((0..9).all(|y| canvas.get(3, y) == WHITE));
+        // 右声道只有中心线上的一个像素
+        assert_eq!(canvas.get(3, 13), WHITE);
+        assert_eq!(canvas.get(3, 10), BLACK);
+        assert_eq!(canvas.get(3, 16), BLACK);
+    }
+
+    #[test]
+    fn test_spectrogram_peak_bin() {
+        // 2 kHz 正弦，8 kHz 采样 → 位于频率轴中间
+        let samples: Vec<f32> = (0..4096)
+            .map(|i| (2.0 * std::f32::consts::PI * 2000.0 * i as f32 / 8000.0).sin())
+            .collect();
+        let settings = FeatureSettings::new(FeatureKind::Stft);
+        let canvas = render_spectrogram(&samples, 8000, 1, 16, 64, &settings).unwrap();
+
+        let brightest = SPECTROGRAM_PALETTE[SPECTROGRAM_PALETTE.len() - 1];
+        assert_eq!(canvas.get(5, 31), brightest);
+        assert_ne!(canvas.get(5, 2), brightest);
+        assert_ne!(canvas.get(5, 60), brightest);
+    }
+
+    #[test]
+    fn test_invalid_size() {
+        let style = WaveformStyle {
+            width: 0,
+            channel_height: 10,
+            background: BLACK,
+            color: WHITE,
+        };
+        assert!(render_waveform(&[0.0], 1, &style).is_err());
+    }
+}
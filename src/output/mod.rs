@@ -1 +1,10 @@
+pub mod array_reader;
 pub mod array_writer;
+pub mod audio_writer;
+pub mod data_writer;
+pub mod flac_encoder;
+pub mod image_renderer;
+pub mod number_format;
+pub mod png_encoder;
+pub mod sample_format;
+pub mod template;
@@ -0,0 +1,152 @@
+//! 数组元素的文本格式：定点小数、最短往返表示、十六进制浮点和位模式
+
+use crate::cli::args::FloatFormat;
+use anyhow::Result;
+
+/// 生成源码的目标语言，决定哪些浮点写法能被编译
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    C,
+    JavaScript,
+}
+
+impl Language {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::Rust => "Rust",
+            Language::C => "C",
+            Language::JavaScript => "JavaScript",
+        }
+    }
+}
+
+/// 检查浮点写法是否为目标语言的合法字面量：十六进制浮点只有 C 支持，位模式只有 Rust 支持
+pub fn check_float_format(float_format: FloatFormat, language: Language) -> Result<()> {
+    match (float_format, language) {
+        (FloatFormat::HexFloat, Language::Rust | Language::JavaScript) => anyhow::bail!(
+            "--float-format hex-float 生成 C99 十六进制浮点字面量，{} 不支持（可使用 shortest 精确表示）",
+            language.name()
+        ),
+        (FloatFormat::Bits, Language::C | Language::JavaScript) => anyhow::bail!(
+            "--float-format bits 生成 Rust 的 f32::from_bits(..)，{} 不支持（可使用 shortest 精确表示）",
+            language.name()
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// 浮点样本转换为字面量文本；`is_f32` 决定最短表示和位模式的精度
+pub fn format_float(value: f64, is_f32: bool, float_format: FloatFormat, precision: usize) -> String {
+    match float_format {
+        FloatFormat::Fixed => format!("{:.*}", precision, value),
+        // Debug 输出保证能精确还原，并且总是带有小数点或指数
+        FloatFormat::Shortest if is_f32 => format!("{:?}", value as f32),
+        FloatFormat::Shortest => format!("{:?}", value),
+        FloatFormat::HexFloat => hex_float(value),
+        FloatFormat::Bits if is_f32 => format!("f32::from_bits(0x{:08X})", (value as f32).to_bits()),
+        FloatFormat::Bits => format!("f64::from_bits(0x{:016X})", value.to_bits()),
+    }
+}
+
+/// 整数字面量；十六进制时按 `bits` 补足位数，负数写为 `-0x...`
+pub fn format_int(value: i64, bits: u32, hex: bool) -> String {
+    if !hex {
+        return value.to_string();
+    }
+
+    let digits = bits.div_ceil(4) as usize;
+    let sign = if value < 0 { "-" } else { "" };
+    format!("{}0x{:0width$X}", sign, value.unsigned_abs(), width = digits)
+}
+
+/// C99 风格的十六进制浮点数，例如 0.75 → `0x1.8p-1`
+pub fn hex_float(value: f64) -> String {
+    let sign = if value.is_sign_negative() { "-" } else { "" };
+    if value == 0.0 {
+        return format!("{}0x0p+0", sign);
+    }
+
+    let bits = value.to_bits();
+    let biased_exponent = ((bits >> 52) & 0x7FF) as i64;
+    let mantissa = bits & ((1u64 << 52) - 1);
+    // 非规格化数的隐含位为 0
+    let (lead, exponent) = if biased_exponent == 0 {
+        (0, -1022)
+    } else {
+        (1, biased_exponent - 1023)
+    };
+
+    let fraction = format!("{:013x}", mantissa);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        format!("{}0x{}p{:+}", sign, lead, exponent)
+    } else {
+        format!("{}0x{}.{}p{:+}", sign, lead, fraction, exponent)
+    }
+}
+
+/// 解析不带符号的十六进制浮点数（小写，以 `0x` 开头）
+pub fn parse_hex_float(text: &str) -> Option<f64> {
+    let body = text.strip_prefix("0x")?;
+    let (mantissa, exponent) = body.split_once('p')?;
+    // C 的 f/l 后缀只能出现在指数之后
+    let exponent: i32 = exponent.trim_end_matches(['f', 'l']).parse().ok()?;
+
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{}{}", int_part, frac_part);
+    if digits.is_empty() || digits.len() > 15 {
+        return None;
+    }
+
+    let value = u64::from_str_radix(&digits, 16).ok()? as f64;
+    let scale = exponent - 4 * frac_part.len() as i32;
+    // 分两步缩放，避免 2^scale 在非规格化范围内溢出
+    Some(value * 2f64.powi(scale / 2) * 2f64.powi(scale - scale / 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_float_styles() {
+        assert_eq!(format_float(0.5, true, FloatFormat::Fixed, 3), "0.500");
+        assert_eq!(format_float(0.1f32 as f64, true, FloatFormat::Shortest, 0), "0.1");
+        assert_eq!(format_float(0.0, true, FloatFormat::Shortest, 0), "0.0");
+        assert_eq!(format_float(0.5, true, FloatFormat::Bits, 0), "f32::from_bits(0x3F000000)");
+        assert_eq!(format_float(-1.0, false, FloatFormat::Bits, 0), "f64::from_bits(0xBFF0000000000000)");
+    }
+
+    #[test]
+    fn test_float_format_targets() {
+        assert!(check_float_format(FloatFormat::HexFloat, Language::C).is_ok());
+        assert!(check_float_format(FloatFormat::HexFloat, Language::Rust).is_err());
+        assert!(check_float_format(FloatFormat::Bits, Language::Rust).is_ok());
+        assert!(check_float_format(FloatFormat::Bits, Language::C).is_err());
+        assert!(check_float_format(FloatFormat::Bits, Language::JavaScript).is_err());
+        assert!(check_float_format(FloatFormat::Shortest, Language::JavaScript).is_ok());
+    }
+
+    #[test]
+    fn test_hex_ints() {
+        assert_eq!(format_int(255, 16, true), "0x00FF");
+        assert_eq!(format_int(-32768, 16, true), "-0x8000");
+        assert_eq!(format_int(-5, 24, true), "-0x000005");
+        assert_eq!(format_int(-5, 24, false), "-5");
+    }
+
+    #[test]
+    fn test_hex_float_round_trip() {
+        assert_eq!(hex_float(0.75), "0x1.8p-1");
+        assert_eq!(hex_float(1.0), "0x1p+0");
+        assert_eq!(hex_float(-0.0), "-0x0p+0");
+
+        for value in [0.75, 1.0, 0.1, 1e-3, 0.123456789, f64::MIN_POSITIVE / 4.0, (0.3f32) as f64] {
+            let text = hex_float(value);
+            assert_eq!(parse_hex_float(&text), Some(value), "{}", text);
+        }
+        assert_eq!(parse_hex_float("0x1.8p-1f"), Some(0.75));
+        assert_eq!(parse_hex_float("0x10"), None);
+    }
+}
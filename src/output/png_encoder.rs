@@ -0,0 +1,113 @@
+//! 简单的 PNG 编码器
+//!
+//! 只输出 8 位 RGB 图像，每行使用 None 过滤，IDAT 中的 zlib 流由 flate2 压缩。
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use std::io::Write;
+
+/// PNG 文件签名
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+/// 将按行排列的 RGB 像素编码为完整的 PNG 文件
+pub fn encode_rgb(pixels: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let row_bytes = width as usize * 3;
+    debug_assert_eq!(pixels.len(), row_bytes * height as usize);
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // 位深 8，颜色类型 2（RGB），压缩、过滤、隔行方式均为 0
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+    // 每行前加过滤类型字节 0
+    let mut raw = Vec::with_capacity((row_bytes + 1) * height as usize);
+    for row in pixels.chunks_exact(row_bytes.max(1)).take(height as usize) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    let mut png = Vec::new();
+    png.extend_from_slice(&SIGNATURE);
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib(&raw));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    // CRC 覆盖类型和数据，不含长度
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// 压缩为 zlib 流
+fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::with_capacity(data.len() / 4), Compression::default());
+    // 写入内存缓冲区不会失败
+    encoder.write_all(data).expect("写入内存缓冲区失败");
+    encoder.finish().expect("写入内存缓冲区失败")
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_png_structure() {
+        let pixels = vec![255, 0, 0, 0, 255, 0, 0, 0, 255, 255, 255, 255];
+        let png = encode_rgb(&pixels, 2, 2);
+
+        assert_eq!(&png[..8], &SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 2, 0, 0, 0, 2]);
+        assert_eq!(&png[png.len() - 12..], &[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]);
+
+        // IDAT 解压后为每行一个过滤类型字节 + 像素
+        let idat_len = u32::from_be_bytes([png[33], png[34], png[35], png[36]]) as usize;
+        assert_eq!(&png[37..41], b"IDAT");
+        let raw = inflate(&png[41..41 + idat_len]);
+        assert_eq!(raw, [0, 255, 0, 0, 0, 255, 0, 0, 0, 0, 255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_flat_image_is_compressed() {
+        // 纯色 200×200 图像原始数据约 120KB，压缩后应远小于原始大小
+        let pixels = vec![128u8; 200 * 200 * 3];
+        let png = encode_rgb(&pixels, 200, 200);
+        assert!(png.len() < pixels.len() / 50, "png = {} 字节", png.len());
+
+        let idat_len = u32::from_be_bytes([png[33], png[34], png[35], png[36]]) as usize;
+        let raw = inflate(&png[41..41 + idat_len]);
+        assert_eq!(raw.len(), (200 * 3 + 1) * 200);
+        assert!(raw.chunks(601).all(|row| row[0] == 0 && row[1..].iter().all(|&b| b == 128)));
+    }
+
+    fn inflate(data: &[u8]) -> Vec<u8> {
+        use std::io::Read;
+        let mut raw = Vec::new();
+        flate2::read::ZlibDecoder::new(data).read_to_end(&mut raw).unwrap();
+        raw
+    }
+}
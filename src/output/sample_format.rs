@@ -0,0 +1,205 @@
+//! 浮点样本与整数/定点格式之间的转换
+//!
+//! 采用非对称映射：`bits` 位整数的范围为 `-2^(bits-1) ..= 2^(bits-1) - 1`，
+//! 浮点样本乘以 `2^(bits-1)` 后四舍五入并饱和，因此 -1.0 对应最小值，
+//! 而 1.0 饱和到最大值。解码时除以同一个 `2^(bits-1)`，整数可以无损往返。
+//!
+//! 直通的整数样本（见 [`Sample`]）在位数之间直接换算，舍入和饱和方式与浮点路径相同。
+
+use crate::audio::pipeline::PARALLEL_CHUNK;
+use crate::audio::simd;
+use crate::cli::args::OutputFormat;
+use rayon::prelude::*;
+
+/// 浮点样本量化为指定位数的有符号整数
+pub fn quantize(sample: f32, bits: u32) -> i32 {
+    let scale = full_scale(bits);
+    (sample as f64 * scale).round().clamp(-scale, scale - 1.0) as i32
+}
+
+/// 批量量化，分块并行并使用向量化内核，结果与逐个调用 `quantize` 相同
+pub fn quantize_all(samples: &[f32], bits: u32) -> Vec<i32> {
+    let mut values = vec![0; samples.len()];
+    values
+        .par_chunks_mut(PARALLEL_CHUNK)
+        .zip(samples.par_chunks(PARALLEL_CHUNK))
+        .for_each(|(output, input)| simd::quantize(input, bits, output));
+    values
+}
+
+/// 写出器接受的样本类型：处理后的浮点样本，或不经过浮点处理的整数 PCM 样本
+///
+/// 整数样本按类型的全部位数表示满量程（i8、i16、i32），量化时只做整数移位和舍入，
+/// 结果与对应浮点值经 `quantize` 相同，但 32 位样本也能逐位保留。
+pub trait Sample: Copy + Send + Sync {
+    /// 量化为 `bits` 位有符号整数
+    fn quantize(self, bits: u32) -> i32;
+
+    /// 批量量化，结果与逐个调用 `quantize` 相同
+    fn quantize_all(samples: &[Self], bits: u32) -> Vec<i32> {
+        samples
+            .par_iter()
+            .with_min_len(PARALLEL_CHUNK)
+            .map(|&v| v.quantize(bits))
+            .collect()
+    }
+
+    /// 还原为浮点样本
+    fn to_f32(self) -> f32;
+}
+
+impl Sample for f32 {
+    fn quantize(self, bits: u32) -> i32 {
+        quantize(self, bits)
+    }
+
+    fn quantize_all(samples: &[f32], bits: u32) -> Vec<i32> {
+        quantize_all(samples, bits)
+    }
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+}
+
+macro_rules! impl_integer_sample {
+    ($($t:ty),*) => {$(
+        impl Sample for $t {
+            fn quantize(self, bits: u32) -> i32 {
+                requantize(self as i64, <$t>::BITS, bits)
+            }
+
+            fn to_f32(self) -> f32 {
+                dequantize(self as i64, <$t>::BITS)
+            }
+        }
+    )*};
+}
+
+impl_integer_sample!(i8, i16, i32);
+
+/// `from` 位整数换算为 `to` 位，舍入和饱和方式与 `quantize` 相同；位数增加时只是左移
+pub fn requantize(value: i64, from: u32, to: u32) -> i32 {
+    if to >= from {
+        return (value << (to - from)) as i32;
+    }
+    let shift = from - to;
+    let magnitude = (value.abs() + (1i64 << (shift - 1))) >> shift;
+    let max = (1i64 << (to - 1)) - 1;
+    (if value < 0 { -magnitude } else { magnitude }).clamp(-max - 1, max) as i32
+}
+
+/// 批量编码，结果与逐个调用 `encode` 相同；浮点格式返回 None
+pub fn encode_all<S: Sample>(samples: &[S], format: OutputFormat) -> Option<Vec<i64>> {
+    let bits = format.bits()?;
+    let offset = if format.is_unsigned() { 1i64 << (bits - 1) } else { 0 };
+    Some(
+        S::quantize_all(samples, bits)
+            .into_par_iter()
+            .with_min_len(PARALLEL_CHUNK)
+            .map(|v| v as i64 + offset)
+            .collect(),
+    )
+}
+
+/// 有符号整数还原为浮点样本
+pub fn dequantize(value: i64, bits: u32) -> f32 {
+    (value as f64 / full_scale(bits)) as f32
+}
+
+/// 按输出格式编码整数样本，无符号格式加上偏移量；浮点格式返回 None
+pub fn encode(sample: f32, format: OutputFormat) -> Option<i64> {
+    let bits = format.bits()?;
+    let value = quantize(sample, bits) as i64;
+    Some(if format.is_unsigned() { value + (1i64 << (bits - 1)) } else { value })
+}
+
+/// 与 `encode` 相同，但接受直通的整数样本
+pub fn encode_sample<S: Sample>(sample: S, format: OutputFormat) -> Option<i64> {
+    let bits = format.bits()?;
+    let value = sample.quantize(bits) as i64;
+    Some(if format.is_unsigned() { value + (1i64 << (bits - 1)) } else { value })
+}
+
+/// `encode` 的逆过程；浮点格式原样返回
+pub fn decode(value: f64, format: OutputFormat) -> f32 {
+    match format.bits() {
+        None => value as f32,
+        Some(bits) => {
+            let offset = if format.is_unsigned() { full_scale(bits) } else { 0.0 };
+            ((value - offset) / full_scale(bits)) as f32
+        }
+    }
+}
+
+/// 24 位样本按小端序拆成 3 个字节
+pub fn pack_i24(value: i64) -> [u8; 3] {
+    let bytes = (value as i32).to_le_bytes();
+    [bytes[0], bytes[1], bytes[2]]
+}
+
+/// 3 个小端序字节还原为带符号的 24 位样本
+pub fn unpack_i24(bytes: [u8; 3]) -> i64 {
+    (i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8) as i64
+}
+
+fn full_scale(bits: u32) -> f64 {
+    (1u64 << (bits - 1)) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_asymmetric_range() {
+        assert_eq!(quantize(-1.0, 16), -32768);
+        assert_eq!(quantize(1.0, 16), 32767);
+        assert_eq!(quantize(0.0, 16), 0);
+        assert_eq!(quantize(-2.0, 8), -128);
+        assert_eq!(quantize(-1.0, 32), i32::MIN);
+        assert_eq!(quantize(1.0, 32), i32::MAX);
+        assert_eq!(quantize(-1.0, 24), -(1 << 23));
+    }
+
+    #[test]
+    fn test_integer_round_trip_is_lossless() {
+        for value in [-32768i64, -1, 0, 1, 12345, 32767] {
+            assert_eq!(quantize(dequantize(value, 16), 16) as i64, value);
+        }
+    }
+
+    #[test]
+    fn test_unsigned_offset_binary() {
+        assert_eq!(encode(0.0, OutputFormat::U8), Some(128));
+        assert_eq!(encode(-1.0, OutputFormat::U8), Some(0));
+        assert_eq!(encode(1.0, OutputFormat::U8), Some(255));
+        assert_eq!(encode(0.0, OutputFormat::U16), Some(32768));
+        assert_eq!(decode(128.0, OutputFormat::U8), 0.0);
+        assert_eq!(decode(0.0, OutputFormat::U16), -1.0);
+        assert_eq!(encode(0.5, OutputFormat::F32), None);
+    }
+
+    #[test]
+    fn test_requantize_matches_float_path() {
+        for value in [-32768i64, -32767, -129, -128, -127, -1, 0, 1, 127, 128, 383, 384, 32767] {
+            let sample = dequantize(value, 16);
+            for bits in [8, 16, 24, 32] {
+                assert_eq!(requantize(value, 16, bits), quantize(sample, bits), "{} → {} 位", value, bits);
+            }
+        }
+        // 32 位样本不经过浮点，低位得以保留
+        assert_eq!(i32::quantize_all(&[0x1234_5677, i32::MIN], 32), vec![0x1234_5677, i32::MIN]);
+        assert_eq!(i32::quantize_all(&[0x1234_5677], 24), vec![0x12_3456]);
+        assert_eq!(i8::quantize_all(&[-128, 127], 16), vec![-32768, 32512]);
+    }
+
+    #[test]
+    fn test_i24_packing() {
+        for value in [-(1i64 << 23), -1, 0, 1, 0x123456, (1 << 23) - 1] {
+            assert_eq!(unpack_i24(pack_i24(value)), value);
+        }
+        assert_eq!(pack_i24(0x123456), [0x56, 0x34, 0x12]);
+        assert_eq!(pack_i24(-1), [0xFF, 0xFF, 0xFF]);
+    }
+}
@@ -0,0 +1,232 @@
+//! 数组输出模板
+//!
+//! 模板使用简单的占位符语法：
+//!
+//! - `{{name}}`：替换为变量，例如 `{{sample_rate}}`、`{{samples}}`、`{{NAME}}`
+//! - `{{#chunks N}} ... {{/chunks}}`：把样本按每块 N 个循环输出，块内可使用
+//!   `{{chunk_index}}`、`{{chunk_offset}}`、`{{chunk_len}}`、`{{chunk_values}}`
+//!   以及 `{{separator}}`（最后一块为空，其余为 `,`）
+//!
+//! 模板路径写为 `builtin:<名称>` 时使用内置模板，见 [`BUILTIN_NAMES`]。
+
+use crate::output::number_format::Language;
+use std::collections::HashMap;
+use anyhow::Result;
+
+const CHUNKS_OPEN: &str = "#chunks";
+const CHUNKS_CLOSE: &str = "/chunks";
+
+/// 内置模板的路径前缀
+pub const BUILTIN_PREFIX: &str = "builtin:";
+/// 内置模板名称
+pub const BUILTIN_NAMES: [&str; 2] = ["c-header", "js-float32array"];
+
+const C_HEADER: &str = "\
+// {{metadata}}
+#pragma once
+#include <stdint.h>
+
+#define {{NAME}}_SAMPLE_RATE {{sample_rate}}
+#define {{NAME}}_CHANNELS {{channels}}
+#define {{NAME}}_LENGTH {{elements}}
+
+static const {{c_type}} {{name}}[{{elements}}] = {
+{{#chunks 16}}    {{chunk_values}}{{separator}}
+{{/chunks}}};
+";
+
+const JS_FLOAT32ARRAY: &str = "\
+// {{metadata}}
+export const {{NAME}}_SAMPLE_RATE = {{sample_rate}};
+export const {{NAME}}_CHANNELS = {{channels}};
+
+export const {{name}} = new Float32Array([
+{{#chunks 16}}  {{chunk_values}}{{separator}}
+{{/chunks}}]);
+";
+
+/// 读取模板：`builtin:<名称>` 返回内置模板，其他值按文件路径读取
+pub fn load(path: &str) -> Result<String> {
+    match path.strip_prefix(BUILTIN_PREFIX) {
+        Some("c-header") => Ok(C_HEADER.to_string()),
+        Some("js-float32array") => Ok(JS_FLOAT32ARRAY.to_string()),
+        Some(name) => anyhow::bail!("未知的内置模板: {}（可用: {}）", name, BUILTIN_NAMES.join(", ")),
+        None => std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("无法读取模板文件 {}: {}", path, e)),
+    }
+}
+
+/// 内置模板生成的语言；自定义模板的语言未知，返回 `None`
+pub fn language(path: &str) -> Option<Language> {
+    match path.strip_prefix(BUILTIN_PREFIX)? {
+        "c-header" => Some(Language::C),
+        "js-float32array" => Some(Language::JavaScript),
+        _ => None,
+    }
+}
+
+/// 模板渲染所需的数据
+pub struct TemplateContext {
+    /// 全局变量
+    pub variables: HashMap<String, String>,
+    /// 已格式化的样本文本
+    pub values: Vec<String>,
+}
+
+pub fn render(template: &str, context: &TemplateContext) -> Result<String> {
+    render_with(template, context, None)
+}
+
+fn render_with(
+    template: &str,
+    context: &TemplateContext,
+    chunk: Option<&HashMap<&str, String>>,
+) -> Result<String> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after_open = &rest[start + 2..];
+        let end = after_open
+            .find("}}")
+            .ok_or_else(|| anyhow::anyhow!("模板中的 {{{{ 没有闭合"))?;
+        let tag = after_open[..end].trim();
+        rest = &after_open[end + 2..];
+
+        if let Some(size) = tag.strip_prefix(CHUNKS_OPEN) {
+            if chunk.is_some() {
+                anyhow::bail!("模板不支持嵌套的 {{{{#chunks}}}}");
+            }
+            let size: usize = size
+                .trim()
+                .parse()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| anyhow::anyhow!("无效的块大小: {{{{{}}}}}", tag))?;
+
+            let (body, remaining) = split_block(rest)?;
+            output.push_str(&render_chunks(body, context, size)?);
+            rest = remaining;
+        } else if tag == CHUNKS_CLOSE {
+            anyhow::bail!("模板中多余的 {{{{/chunks}}}}");
+        } else {
+            let value = chunk
+                .and_then(|vars| vars.get(tag))
+                .or_else(|| context.variables.get(tag))
+                .ok_or_else(|| anyhow::anyhow!("模板中未知的变量: {}", tag))?;
+            output.push_str(value);
+        }
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+fn render_chunks(body: &str, context: &TemplateContext, size: usize) -> Result<String> {
+    let chunk_count = context.values.len().div_ceil(size);
+    let mut output = String::new();
+
+    for (index, values) in context.values.chunks(size).enumerate() {
+        let mut vars = HashMap::new();
+        vars.insert("chunk_index", index.to_string());
+        vars.insert("chunk_offset", (index * size).to_string());
+        vars.insert("chunk_len", values.len().to_string());
+        vars.insert("chunk_values", values.join(", "));
+        vars.insert(
+            "separator",
+            if index + 1 < chunk_count { "," } else { "" }.to_string(),
+        );
+        output.push_str(&render_with(body, context, Some(&vars))?);
+    }
+
+    Ok(output)
+}
+
+/// 返回 `{{/chunks}}` 之前的块内容和之后的剩余模板
+fn split_block(template: &str) -> Result<(&str, &str)> {
+    let mut search_from = 0;
+    while let Some(start) = template[search_from..].find("{{") {
+        let start = search_from + start;
+        let end = template[start..]
+            .find("}}")
+            .ok_or_else(|| anyhow::anyhow!("模板中的 {{{{ 没有闭合"))?;
+        if template[start + 2..start + end].trim() == CHUNKS_CLOSE {
+            return Ok((&template[..start], &template[start + end + 2..]));
+        }
+        search_from = start + end + 2;
+    }
+
+    anyhow::bail!("{{{{#chunks}}}} 缺少对应的 {{{{/chunks}}}}")
+}
+
+/// 把文件名转换为合法的标识符，例如 `my-sound.rs` → `my_sound`
+pub fn identifier(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    ident
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> TemplateContext {
+        let mut variables = HashMap::new();
+        variables.insert("name".to_string(), "beep".to_string());
+        variables.insert("length".to_string(), "5".to_string());
+        TemplateContext {
+            variables,
+            values: ["1", "2", "3", "4", "5"].iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_variables() {
+        let output = render("int {{ name }}[{{length}}];", &context()).unwrap();
+        assert_eq!(output, "int beep[5];");
+    }
+
+    #[test]
+    fn test_chunks() {
+        let template = "{\n{{#chunks 2}}  /* {{chunk_offset}} */ {{chunk_values}}{{separator}}\n{{/chunks}}}";
+        let output = render(template, &context()).unwrap();
+        assert_eq!(output, "{\n  /* 0 */ 1, 2,\n  /* 2 */ 3, 4,\n  /* 4 */ 5\n}");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(render("{{unknown}}", &context()).is_err());
+        assert!(render("{{name", &context()).is_err());
+        assert!(render("{{#chunks 2}}{{chunk_values}}", &context()).is_err());
+        assert!(render("{{#chunks 0}}{{/chunks}}", &context()).is_err());
+        assert!(render("{{/chunks}}", &context()).is_err());
+        assert!(render("{{chunk_values}}", &context()).is_err());
+    }
+
+    #[test]
+    fn test_builtin_templates() {
+        for name in BUILTIN_NAMES {
+            let template = load(&format!("{}{}", BUILTIN_PREFIX, name)).unwrap();
+            let mut context = context();
+            for key in ["metadata", "NAME", "sample_rate", "channels", "elements", "c_type"] {
+                context.variables.insert(key.to_string(), "x".to_string());
+            }
+            let output = render(&template, &context).unwrap();
+            assert!(output.contains("1, 2, 3, 4, 5"), "{}", output);
+        }
+        assert!(load("builtin:python").is_err());
+    }
+
+    #[test]
+    fn test_identifier() {
+        assert_eq!(identifier("my-sound"), "my_sound");
+        assert_eq!(identifier("8bit"), "_8bit");
+        assert_eq!(identifier("Intro.Theme"), "intro_theme");
+    }
+}
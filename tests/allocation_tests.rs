@@ -0,0 +1,75 @@
+//! 原地转换 API 的内存分配测试
+//! 使用计数分配器验证预热后重复转换不再分配堆内存
+
+use audio_converter::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// 本文件只有一个测试，计数期间没有其他线程分配内存
+#[test]
+fn test_convert_in_place_does_not_allocate_after_warm_up() {
+    let config = Config {
+        sample_rate: Some(16000),
+        channels: Some(1),
+        gain: -3.0,
+        normalize: true,
+        filters: vec!["highpass:80".parse().unwrap(), "lowpass:6000".parse().unwrap()],
+        ..Default::default()
+    };
+    let converter = AudioConverter::new(config);
+    let clips: Vec<Vec<f32>> = [4800, 9600, 2400]
+        .iter()
+        .map(|&len| (0..len).map(|i| (i as f32 * 0.03).sin() * 0.8).collect())
+        .collect();
+
+    let mut state = ConvertState::default();
+    let mut buffer = AudioBuffer {
+        samples: Vec::new(),
+        sample_rate: 0,
+        channels: 0,
+    };
+    let mut run = |clip: &[f32]| {
+        buffer.samples.clear();
+        buffer.samples.extend_from_slice(clip);
+        buffer.sample_rate = 48000;
+        buffer.channels = 2;
+        converter.convert_in_place(&mut buffer, &mut state).unwrap();
+        assert_eq!(buffer.channels, 1);
+    };
+
+    // 预热：缓冲区增长到最长片段所需的容量
+    for clip in &clips {
+        run(clip);
+    }
+
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    for _ in 0..10 {
+        for clip in &clips {
+            run(clip);
+        }
+    }
+    assert_eq!(ALLOCATIONS.load(Ordering::SeqCst) - before, 0);
+}
@@ -177,8 +177,15 @@ fn test_format_conversion_accuracy() {
     let formats = vec![
         OutputFormat::F32,
         OutputFormat::F64,
+        OutputFormat::I8,
+        OutputFormat::U8,
         OutputFormat::I16,
+        OutputFormat::U16,
+        OutputFormat::I24,
+        OutputFormat::I24Packed,
         OutputFormat::I32,
+        OutputFormat::Q15,
+        OutputFormat::Q31,
     ];
 
     for format in formats {
@@ -204,8 +211,10 @@ fn test_format_conversion_accuracy() {
             let max_error = match format {
                 OutputFormat::F32 => 0.0001,  // 32位浮点
                 OutputFormat::F64 => 0.00001, // 64位浮点
-                OutputFormat::I16 => 0.001,   // 16位整数
-                OutputFormat::I32 => 0.00001, // 32位整数
+                OutputFormat::I8 | OutputFormat::U8 => 0.01, // 8位整数
+                OutputFormat::I16 | OutputFormat::U16 | OutputFormat::Q15 => 0.001, // 16位整数
+                OutputFormat::I24 | OutputFormat::I24Packed => 0.0001, // 24位整数
+                OutputFormat::I32 | OutputFormat::Q31 => 0.00001, // 32位整数
             };
 
             assert!(
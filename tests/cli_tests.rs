@@ -244,12 +244,732 @@ fn test_cli_with_config_file() {
 }
 
 #[test]
-fn test_cli_invalid_format() {
+fn test_cli_with_filter_option() {
     let temp_input = create_test_wav_file(44100, 2, 0.1);
     let temp_output = NamedTempFile::new().unwrap();
 
     let mut cmd = Command::cargo_bin("audio-converter").unwrap();
     cmd.args(&[
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        temp_output.path().to_str().unwrap(),
+        "--filter",
+        "dcblock",
+        "--filter",
+        "lowpass:8000:0.707",
+    ])
+    .assert()
+    .success();
+}
+
+#[test]
+fn test_cli_invalid_filter() {
+    let temp_input = create_test_wav_file(44100, 2, 0.1);
+    let temp_output = NamedTempFile::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        temp_output.path().to_str().unwrap(),
+        "--filter",
+        "bogus:100",
+    ])
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("未知的滤波器类型"));
+}
+
+#[test]
+fn test_cli_wav_output() {
+    let temp_input = create_test_wav_file(44100, 2, 0.1);
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let output_path = temp_dir.path().join("out.wav");
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+        "-c",
+        "1",
+        "--wav-format",
+        "pcm24",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("音频已写入"));
+
+    let reader = hound::WavReader::open(&output_path).unwrap();
+    assert_eq!(reader.spec().channels, 1);
+    assert_eq!(reader.spec().bits_per_sample, 24);
+    assert_eq!(reader.spec().sample_rate, 44100);
+}
+
+#[test]
+fn test_cli_flac_output() {
+    let temp_input = create_test_wav_file(22050, 1, 0.1);
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let output_path = temp_dir.path().join("out.flac");
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+        "-f",
+        "i16",
+    ])
+    .assert()
+    .success();
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args(["info", output_path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("容器格式: FLAC"))
+        .stdout(predicate::str::contains("帧数: 2205"));
+}
+
+#[test]
+fn test_cli_preview_option() {
+    let temp_input = create_test_wav_file(44100, 2, 0.1);
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let output_path = temp_dir.path().join("out.rs");
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+        "-f",
+        "i16",
+        "--preview",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("试听文件已写入"));
+
+    assert!(std::fs::read_to_string(&output_path).unwrap().contains("[i16;"));
+    let reader = hound::WavReader::open(temp_dir.path().join("out.wav")).unwrap();
+    assert_eq!(reader.spec().bits_per_sample, 16);
+    assert_eq!(reader.len(), 4410 * 2);
+}
+
+#[test]
+fn test_cli_decode_array_round_trip() {
+    let temp_input = create_test_wav_file(22050, 2, 0.1);
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let array_path = temp_dir.path().join("out.rs");
+    let wav_path = temp_dir.path().join("restored.wav");
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        array_path.to_str().unwrap(),
+        "-f",
+        "i16",
+    ])
+    .assert()
+    .success();
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "decode-array",
+        array_path.to_str().unwrap(),
+        "-o",
+        wav_path.to_str().unwrap(),
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("采样率=22050Hz, 声道数=2"));
+
+    let original: Vec<i16> = hound::WavReader::open(temp_input.path())
+        .unwrap()
+        .samples::<i16>()
+        .map(|s| s.unwrap())
+        .collect();
+    let restored: Vec<i16> = hound::WavReader::open(&wav_path)
+        .unwrap()
+        .samples::<i16>()
+        .map(|s| s.unwrap())
+        .collect();
+
+    assert_eq!(restored, original);
+}
+
+#[test]
+fn test_cli_decode_array_requires_sample_rate() {
+    let mut array_file = NamedTempFile::new().unwrap();
+    array_file.write_all(b"int16_t x[] = {1, 2, 3};").unwrap();
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let wav_path = temp_dir.path().join("out.wav");
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "decode-array",
+        array_file.path().to_str().unwrap(),
+        "-o",
+        wav_path.to_str().unwrap(),
+    ])
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("--sample-rate"));
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "decode-array",
+        array_file.path().to_str().unwrap(),
+        "-o",
+        wav_path.to_str().unwrap(),
+        "-s",
+        "8000",
+    ])
+    .assert()
+    .success();
+}
+
+#[test]
+fn test_cli_unsigned_and_packed_formats() {
+    let temp_input = create_test_wav_file(8000, 1, 0.01);
+    let temp_dir = tempfile::TempDir::new().unwrap();
+
+    for (format, array_type) in [("u8", "[u8; 80]"), ("i24-packed", "[u8; 240]"), ("q31", "[i32; 80]")] {
+        let output_path = temp_dir.path().join(format!("{}.rs", format));
+        let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+        cmd.args([
+            "-i",
+            temp_input.path().to_str().unwrap(),
+            "-o",
+            output_path.to_str().unwrap(),
+            "-f",
+            format,
+        ])
+        .assert()
+        .success();
+
+        let content = std::fs::read_to_string(&output_path).unwrap();
+        assert!(content.contains(array_type), "{}", format);
+    }
+}
+
+#[test]
+fn test_cli_layout_option() {
+    let temp_input = create_test_wav_file(8000, 2, 0.01);
+    let temp_output = NamedTempFile::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        temp_output.path().to_str().unwrap(),
+        "-f",
+        "i16",
+        "--layout",
+        "per-channel",
+    ])
+    .assert()
+    .success();
+
+    let content = std::fs::read_to_string(temp_output.path()).unwrap();
+    assert!(content.contains("const AUDIO_FRAMES: usize = 80;"));
+    assert!(content.contains("const AUDIO_LEFT: [i16; 80]"));
+    assert!(content.contains("const AUDIO_RIGHT: [i16; 80]"));
+}
+
+#[test]
+fn test_cli_template_option() {
+    let temp_input = create_test_wav_file(8000, 1, 0.01);
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let template_path = temp_dir.path().join("header.tpl");
+    std::fs::write(
+        &template_path,
+        "#pragma once\n#define {{NAME}}_FRAMES {{frames}}\n\
+         static const {{c_type}} {{name}}[] = {\n{{#chunks 16}}  {{chunk_values}}{{separator}}\n{{/chunks}}};\n",
+    )
+    .unwrap();
+    let output_path = temp_dir.path().join("beep-sound.h");
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+        "-f",
+        "u8",
+        "--template",
+        template_path.to_str().unwrap(),
+    ])
+    .assert()
+    .success();
+
+    let content = std::fs::read_to_string(&output_path).unwrap();
+    assert!(content.starts_with("#pragma once\n#define BEEP_SOUND_FRAMES 80\n"));
+    assert!(content.contains("static const uint8_t beep_sound[] = {\n  128, "));
+    assert_eq!(content.lines().count(), 2 + 1 + 5 + 1);
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+        "--template",
+        temp_dir.path().join("missing.tpl").to_str().unwrap(),
+    ])
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("无法读取模板文件"));
+}
+
+#[test]
+fn test_cli_data_outputs() {
+    let temp_input = create_test_wav_file(8000, 2, 0.01);
+    let temp_dir = tempfile::TempDir::new().unwrap();
+
+    let csv_path = temp_dir.path().join("out.csv");
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        csv_path.to_str().unwrap(),
+        "-f",
+        "i16",
+        "--timestamps",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("数据已写入"));
+    let csv = std::fs::read_to_string(&csv_path).unwrap();
+    assert!(csv.starts_with("time,channel_0,channel_1\n0.000000,0,0\n"));
+    assert_eq!(csv.lines().count(), 81);
+
+    let npy_path = temp_dir.path().join("out.npy");
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        npy_path.to_str().unwrap(),
+        "-c",
+        "1",
+    ])
+    .assert()
+    .success();
+    let npy = std::fs::read(&npy_path).unwrap();
+    let header = String::from_utf8_lossy(&npy[10..128]);
+    assert!(header.contains("'descr': '<f4'"));
+    assert!(header.contains("'shape': (80, 1)"));
+
+    let raw_path = temp_dir.path().join("out.raw");
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        raw_path.to_str().unwrap(),
+        "-f",
+        "i16",
+        "--endian",
+        "big",
+    ])
+    .assert()
+    .success();
+    assert_eq!(std::fs::metadata(&raw_path).unwrap().len(), 80 * 2 * 2);
+
+    let json_path = temp_dir.path().join("out.json");
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        json_path.to_str().unwrap(),
+    ])
+    .assert()
+    .success();
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+    assert_eq!(json["metadata"]["sample_rate"], 8000);
+    assert_eq!(json["samples"].as_array().unwrap().len(), 160);
+}
+
+#[test]
+fn test_cli_feature_export() {
+    let temp_input = create_test_wav_file(16000, 2, 0.1);
+    let temp_dir = tempfile::TempDir::new().unwrap();
+
+    let array_path = temp_dir.path().join("mel.rs");
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        array_path.to_str().unwrap(),
+        "--features",
+        "log-mel",
+        "--mel-bands",
+        "24",
+        "--fft-size",
+        "256",
+        "--hop",
+        "128",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("特征提取完成: 类型=log-mel, 帧数=11, 维数=24"));
+    let content = std::fs::read_to_string(&array_path).unwrap();
+    assert!(content.contains("\"shape\":[11,24]"));
+    assert!(content.contains("const FEATURES: [[f32; 24]; 11]"));
+
+    let npy_path = temp_dir.path().join("mfcc.npy");
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        npy_path.to_str().unwrap(),
+        "--features",
+        "mfcc",
+    ])
+    .assert()
+    .success();
+    let npy = std::fs::read(&npy_path).unwrap();
+    assert!(String::from_utf8_lossy(&npy[10..128]).contains("'shape': (7, 13)"));
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        array_path.to_str().unwrap(),
+        "--features",
+        "stft",
+        "--fft-size",
+        "300",
+    ])
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("2 的幂"));
+
+    // 预设中的 C 头文件模板和 i16 格式不能用于特征矩阵
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        temp_dir.path().join("mfcc.h").to_str().unwrap(),
+        "--preset",
+        "esp32-i2s-16k",
+        "--features",
+        "mfcc",
+    ])
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("特征矩阵只能输出为浮点格式"));
+}
+
+#[test]
+fn test_cli_peaks_command() {
+    let temp_input = create_test_wav_file(8000, 2, 0.5);
+    let temp_dir = tempfile::TempDir::new().unwrap();
+
+    let json_path = temp_dir.path().join("peaks.json");
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "peaks",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        json_path.to_str().unwrap(),
+        "--points",
+        "100",
+        "--rms",
+        "--bits",
+        "8",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("峰值计算完成: 点数=100, 声道数=2, 每点帧数=40"));
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+    assert_eq!(json["version"], 2);
+    assert_eq!(json["bits"], 8);
+    assert_eq!(json["samples_per_pixel"], 40);
+    assert_eq!(json["data"].as_array().unwrap().len(), 400);
+    assert_eq!(json["rms"].as_array().unwrap().len(), 200);
+
+    let array_path = temp_dir.path().join("peaks.rs");
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "peaks",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        array_path.to_str().unwrap(),
+        "--samples-per-pixel",
+        "1000",
+    ])
+    .assert()
+    .success();
+    let content = std::fs::read_to_string(&array_path).unwrap();
+    assert!(content.contains("const PEAKS_LENGTH: usize = 4;"));
+    assert!(content.contains("const PEAKS: [i16; 16]"));
+    assert!(!content.contains("PEAKS_RMS"));
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "peaks",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        array_path.to_str().unwrap(),
+        "--bits",
+        "12",
+    ])
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("8 或 16"));
+}
+
+#[test]
+fn test_cli_render_command() {
+    let temp_input = create_test_wav_file(8000, 2, 0.5);
+    let temp_dir = tempfile::TempDir::new().unwrap();
+
+    let waveform_path = temp_dir.path().join("waveform.png");
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "render",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        waveform_path.to_str().unwrap(),
+        "--width",
+        "320",
+        "--height",
+        "60",
+        "--color",
+        "#FF8800",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("(320x120)"));
+    let png = std::fs::read(&waveform_path).unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    assert_eq!(&png[16..24], &[0, 0, 1, 64, 0, 0, 0, 120]);
+
+    let spectrogram_path = temp_dir.path().join("spectrogram.png");
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "render",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        spectrogram_path.to_str().unwrap(),
+        "--mode",
+        "spectrogram",
+        "-c",
+        "1",
+        "--width",
+        "100",
+        "--height",
+        "80",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("(100x80)"));
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "render",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        waveform_path.to_str().unwrap(),
+        "--color",
+        "blue",
+    ])
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("无效的颜色"));
+}
+
+#[test]
+fn test_cli_preset_option() {
+    let temp_input = create_test_wav_file(44100, 2, 0.1);
+    let temp_dir = tempfile::TempDir::new().unwrap();
+
+    let header_path = temp_dir.path().join("sound.h");
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        header_path.to_str().unwrap(),
+        "--preset",
+        "esp32-i2s-16k",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("采样率=16000Hz, 声道数=1"));
+    let content = std::fs::read_to_string(&header_path).unwrap();
+    assert!(content.contains("#define SOUND_SAMPLE_RATE 16000"));
+    assert!(content.contains("static const int16_t sound[1600] = {"));
+
+    // 命令行参数优先于预设，配置文件中的自定义预设优先于内置预设
+    let config_path = temp_dir.path().join("config.toml");
+    std::fs::write(
+        &config_path,
+        "[presets.esp32-i2s-16k]\noutput_format = \"u8\"\nsample_rate = 8000\n",
+    )
+    .unwrap();
+    let array_path = temp_dir.path().join("custom.rs");
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        array_path.to_str().unwrap(),
+        "-C",
+        config_path.to_str().unwrap(),
+        "--preset",
+        "esp32-i2s-16k",
+        "-c",
+        "1",
+        "-s",
+        "11025",
+    ])
+    .assert()
+    .success();
+    let content = std::fs::read_to_string(&array_path).unwrap();
+    assert!(content.contains("\"format\":\"u8\""));
+    assert!(content.contains("\"sample_rate\":11025"));
+    assert!(content.contains("\"channels\":1"));
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        array_path.to_str().unwrap(),
+        "--preset",
+        "amiga",
+    ])
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("未知的预设: amiga"))
+    .stderr(predicate::str::contains("gba-8bit"));
+}
+
+#[test]
+fn test_cli_overrides_config_to_source() {
+    let temp_input = create_test_wav_file(44100, 2, 0.1);
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    std::fs::write(&config_path, "sample_rate = 8000\nchannels = 1\nnormalize = true\n").unwrap();
+
+    // source 恢复为源文件的采样率和声道数，--name 改变常量前缀，--no-metadata 去掉元数据注释
+    let output_path = temp_dir.path().join("beep.rs");
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        temp_input.path().to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+        "-C",
+        config_path.to_str().unwrap(),
+        "-s",
+        "source",
+        "-c",
+        "source",
+        "--name",
+        "beep",
+        "--no-metadata",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("转换完成: 采样率=44100Hz, 声道数=2"));
+    let content = std::fs::read_to_string(&output_path).unwrap();
+    assert!(!content.contains("// 音频元数据"));
+    assert!(content.contains("const BEEP_FRAMES: usize = 4410;"));
+    assert!(content.contains("const BEEP_SAMPLES: [f32; 8820]"));
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args(["-i", temp_input.path().to_str().unwrap(), "-o", output_path.to_str().unwrap(), "-s", "fast"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("source"));
+}
+
+#[test]
+fn test_cli_layered_config() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let xdg = temp_dir.path().join("xdg");
+    let project = temp_dir.path().join("project");
+    let work_dir = project.join("assets");
+    std::fs::create_dir_all(xdg.join("audio-converter")).unwrap();
+    std::fs::create_dir_all(&work_dir).unwrap();
+    std::fs::write(xdg.join("audio-converter/config.toml"), "output_format = \"i8\"\nsample_rate = 22050\n").unwrap();
+    std::fs::write(project.join("audio-converter.toml"), "sample_rate = 16000\nchannels = 1\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    let output = cmd
+        .current_dir(&work_dir)
+        .env("XDG_CONFIG_HOME", &xdg)
+        .env("AUDIO_CONVERTER_GAIN", "-6")
+        .args(["config", "show", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let shown: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(shown["config"]["output_format"], "I8");
+    assert_eq!(shown["config"]["sample_rate"], 16000);
+    assert_eq!(shown["config"]["gain"], -6.0);
+    assert!(shown["sources"]["output_format"].as_str().unwrap().starts_with("用户配置"));
+    assert!(shown["sources"]["channels"].as_str().unwrap().starts_with("项目配置"));
+    assert_eq!(shown["sources"]["gain"], "环境变量 AUDIO_CONVERTER_GAIN");
+    assert_eq!(shown["sources"]["normalize"], "默认值");
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.current_dir(&work_dir)
+        .env("XDG_CONFIG_HOME", &xdg)
+        .args(["config", "show", "--preset", "gba-8bit"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("# 预设 gba-8bit"))
+        .stdout(predicate::str::is_match(r"sample_rate\s+= 18157").unwrap());
+
+    // 命令行参数优先于所有配置层
+    let temp_input = create_test_wav_file(44100, 2, 0.1);
+    let output_path = temp_dir.path().join("out.rs");
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.current_dir(&work_dir)
+        .env("XDG_CONFIG_HOME", &xdg)
+        .env("AUDIO_CONVERTER_CHANNELS", "2")
+        .args([
+            "-i",
+            temp_input.path().to_str().unwrap(),
+            "-o",
+            output_path.to_str().unwrap(),
+            "-s",
+            "8000",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("采样率=8000Hz, 声道数=2"));
+    let content = std::fs::read_to_string(&output_path).unwrap();
+    assert!(content.contains("\"format\":\"i8\""));
+}
+
+#[test]
+fn test_cli_invalid_format() {
+    let temp_input = create_test_wav_file(44100, 2, 0.1);
+    let temp_output = NamedTempFile::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
         "-i",
         temp_input.path().to_str().unwrap(),
         "-o",
@@ -262,7 +982,283 @@ fn test_cli_invalid_format() {
     .stderr(predicate::str::contains("invalid"));
 }
 
+#[test]
+fn test_cli_info_command() {
+    let temp_input = create_test_wav_file(22050, 2, 0.1);
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args(["info", temp_input.path().to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("容器格式: WAV"))
+        .stdout(predicate::str::contains("采样率: 22050Hz"))
+        .stdout(predicate::str::contains("声道数: 2"));
+}
+
+#[test]
+fn test_cli_info_json_output() {
+    let temp_input = create_test_wav_file(44100, 1, 0.1);
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    let output = cmd
+        .args(["info", "--json", temp_input.path().to_str().unwrap()])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(info["container"], "WAV");
+    assert_eq!(info["tracks"][0]["sample_rate"], 44100);
+    assert_eq!(info["tracks"][0]["channels"], 1);
+    assert_eq!(info["tracks"][0]["frames"], 4410);
+}
+
+#[test]
+fn test_cli_info_nonexistent_file() {
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args(["info", "nonexistent.wav"]).assert().failure();
+}
+
+#[test]
+fn test_cli_analyze_command() {
+    let temp_input = create_test_wav_file(44100, 2, 0.5);
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args(["analyze", temp_input.path().to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("积分响度"))
+        .stdout(predicate::str::contains("声道 1:"));
+}
+
+#[test]
+fn test_cli_analyze_json_output() {
+    let temp_input = create_test_wav_file(44100, 1, 0.5);
+
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    let output = cmd
+        .args(["analyze", "--json", temp_input.path().to_str().unwrap()])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["channels"], 1);
+    assert_eq!(report["channel_stats"].as_array().unwrap().len(), 1);
+    assert!(report["channel_stats"][0]["peak"].as_f64().unwrap() > 0.9);
+    assert!(report["integrated_loudness_lufs"].is_number());
+}
+
 // 辅助函数：创建测试WAV文件
+
+#[test]
+fn test_cli_directory_input() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let input_dir = temp_dir.path().join("sounds");
+    std::fs::create_dir_all(input_dir.join("ui")).unwrap();
+    let source = create_test_wav_file(8000, 1, 0.05);
+    std::fs::copy(source.path(), input_dir.join("boom.wav")).unwrap();
+    std::fs::copy(source.path(), input_dir.join("ui/click.wav")).unwrap();
+    std::fs::write(input_dir.join("notes.txt"), "not audio").unwrap();
+
+    let output_dir = temp_dir.path().join("out");
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args([
+        "-i",
+        input_dir.to_str().unwrap(),
+        "-o",
+        output_dir.to_str().unwrap(),
+        "-f",
+        "i16",
+        "--output-ext",
+        "h",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("共转换 2 个文件"));
+    assert!(output_dir.join("boom.h").exists());
+    assert!(output_dir.join("ui/click.h").exists());
+    assert!(!output_dir.join("notes.h").exists());
+
+    // 单个文件失败不影响其他文件，最后返回错误
+    std::fs::write(input_dir.join("broken.wav"), "not a wav file").unwrap();
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.args(["-i", input_dir.to_str().unwrap(), "-o", output_dir.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("broken.wav"))
+        .stderr(predicate::str::contains("3 个文件中有 1 个转换失败"));
+    assert!(output_dir.join("ui/click.rs").exists());
+}
+
+
+#[test]
+fn test_cli_directory_cache() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let input_dir = temp_dir.path().join("sounds");
+    std::fs::create_dir_all(&input_dir).unwrap();
+    let source = create_test_wav_file(8000, 1, 0.05);
+    std::fs::copy(source.path(), input_dir.join("a.wav")).unwrap();
+    std::fs::copy(source.path(), input_dir.join("b.wav")).unwrap();
+    let output_dir = temp_dir.path().join("out");
+
+    let run = |extra: &[&str]| {
+        let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+        cmd.args(["-i", input_dir.to_str().unwrap(), "-o", output_dir.to_str().unwrap()])
+            .args(extra)
+            .assert()
+    };
+
+    run(&[]).success().stdout(predicate::str::contains("共转换 2 个文件，0 个未变化已跳过"));
+    assert!(output_dir.join(".audio-converter-cache.json").exists());
+
+    // 输入和配置都未变化时跳过；修改配置后重新转换
+    run(&[]).success().stdout(predicate::str::contains("共转换 0 个文件，2 个未变化已跳过"));
+    run(&["-f", "i16"]).success().stdout(predicate::str::contains("共转换 2 个文件"));
+
+    // 只有修改过的输入被重新转换
+    let longer = create_test_wav_file(8000, 1, 0.1);
+    std::fs::copy(longer.path(), input_dir.join("b.wav")).unwrap();
+    run(&["-f", "i16"]).success().stdout(predicate::str::contains("共转换 1 个文件，1 个未变化已跳过"));
+
+    // 删除的输出会重新生成
+    std::fs::remove_file(output_dir.join("a.rs")).unwrap();
+    run(&["-f", "i16"])
+        .success()
+        .stdout(predicate::str::contains("输出文件已被删除，重新生成"))
+        .stdout(predicate::str::contains("共转换 1 个文件"));
+
+    // 手动修改的输出不会被覆盖，--force 强制重新生成
+    std::fs::write(output_dir.join("a.rs"), "// hand edited").unwrap();
+    run(&["-f", "i16"])
+        .failure()
+        .stderr(predicate::str::contains("在上次生成后被修改"));
+    assert_eq!(std::fs::read_to_string(output_dir.join("a.rs")).unwrap(), "// hand edited");
+    run(&["-f", "i16", "--force"]).success().stdout(predicate::str::contains("共转换 2 个文件"));
+    assert!(std::fs::read_to_string(output_dir.join("a.rs")).unwrap().contains("AUDIO_SAMPLES"));
+}
+
+#[test]
+fn test_cli_build_manifest() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let sfx_dir = temp_dir.path().join("sfx");
+    std::fs::create_dir_all(&sfx_dir).unwrap();
+    let source = create_test_wav_file(8000, 2, 0.05);
+    std::fs::copy(source.path(), sfx_dir.join("jump.wav")).unwrap();
+    std::fs::copy(source.path(), sfx_dir.join("coin.wav")).unwrap();
+    std::fs::copy(source.path(), temp_dir.path().join("theme.wav")).unwrap();
+
+    let manifest = temp_dir.path().join("audio-assets.toml");
+    std::fs::write(
+        &manifest,
+        r#"
+[defaults]
+output_format = "i16"
+channels = 1
+
+[[jobs]]
+input = "sfx/*.wav"
+output = "out/{stem}.rs"
+name = "sfx_{stem}"
+
+[[jobs]]
+input = "theme.wav"
+output = "out/theme.rs"
+output_format = "f32"
+channels = 2
+"#,
+    )
+    .unwrap();
+
+    let run = |extra: &[&str]| {
+        let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+        cmd.args(["build", manifest.to_str().unwrap()]).args(extra).assert()
+    };
+
+    run(&["--dry-run"])
+        .success()
+        .stdout(predicate::str::contains("3 个文件"))
+        .stdout(predicate::str::contains("coin.rs"));
+    assert!(!temp_dir.path().join("out").exists());
+
+    run(&[]).success().stdout(predicate::str::contains("构建完成！共转换 3 个文件，0 个未变化已跳过"));
+    let jump = std::fs::read_to_string(temp_dir.path().join("out/jump.rs")).unwrap();
+    assert!(jump.contains("SFX_JUMP_SAMPLES: [i16;"));
+    assert!(jump.contains("SFX_JUMP_CHANNELS: usize = 1;"));
+    let theme = std::fs::read_to_string(temp_dir.path().join("out/theme.rs")).unwrap();
+    assert!(theme.contains("[f32;"));
+    assert!(theme.contains("AUDIO_CHANNELS: usize = 2;"));
+
+    // 缓存清单放在构建清单旁边，再次构建时跳过未变化的文件
+    assert!(temp_dir.path().join(".audio-converter-cache.json").exists());
+    run(&[]).success().stdout(predicate::str::contains("共转换 0 个文件，3 个未变化已跳过"));
+
+    std::fs::write(&manifest, "[[jobs]]\ninput = \"sfx/*.wav\"\noutput = \"out.rs\"\n").unwrap();
+    run(&[]).failure().stderr(predicate::str::contains("{stem}"));
+}
+
+#[test]
+fn test_cli_build_relative_template_from_other_directory() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let project = temp_dir.path().join("project");
+    std::fs::create_dir_all(project.join("templates")).unwrap();
+    let source = create_test_wav_file(8000, 1, 0.01);
+    std::fs::copy(source.path(), project.join("beep.wav")).unwrap();
+    std::fs::write(project.join("templates/array.tpl"), "// {{name}}: {{length}} 个样本\n").unwrap();
+    std::fs::write(
+        project.join("audio-assets.toml"),
+        r#"
+[defaults]
+output_settings = { template = "templates/array.tpl" }
+
+[[jobs]]
+input = "beep.wav"
+output = "out/beep.txt"
+"#,
+    )
+    .unwrap();
+
+    // 在清单目录之外运行，模板仍按清单目录查找
+    let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["build", "project/audio-assets.toml"])
+        .assert()
+        .success();
+    let output = std::fs::read_to_string(project.join("out/beep.txt")).unwrap();
+    assert_eq!(output, "// beep: 80 个样本\n");
+}
+
+#[test]
+fn test_cli_jobs_output_is_identical() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let input_dir = temp_dir.path().join("sounds");
+    std::fs::create_dir_all(&input_dir).unwrap();
+    for (name, duration) in [("a.wav", 0.5), ("b.wav", 0.2), ("c.wav", 1.0)] {
+        let source = create_test_wav_file(44100, 2, duration);
+        std::fs::copy(source.path(), input_dir.join(name)).unwrap();
+    }
+
+    let run = |jobs: &str, output: &std::path::Path| {
+        let mut cmd = Command::cargo_bin("audio-converter").unwrap();
+        cmd.args(["-i", input_dir.to_str().unwrap(), "-o", output.to_str().unwrap()])
+            .args(["-f", "i16", "-g", "-3", "--jobs", jobs])
+            .assert()
+    };
+
+    let sequential = temp_dir.path().join("seq");
+    let parallel = temp_dir.path().join("par");
+    run("1", &sequential).success().stdout(predicate::str::contains("共转换 3 个文件"));
+    run("4", &parallel).success().stdout(predicate::str::contains("共转换 3 个文件"));
+    for name in ["a.rs", "b.rs", "c.rs"] {
+        assert_eq!(
+            std::fs::read(sequential.join(name)).unwrap(),
+            std::fs::read(parallel.join(name)).unwrap()
+        );
+    }
+
+    run("0", &parallel).failure().stderr(predicate::str::contains("--jobs 必须大于 0"));
+}
+
 fn create_test_wav_file(sample_rate: u32, channels: u16, duration_seconds: f32) -> NamedTempFile {
     let temp_file = NamedTempFile::new().unwrap();
     let spec = hound::WavSpec {
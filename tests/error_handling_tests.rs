@@ -52,6 +52,39 @@ fn test_missing_config_file_handling() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_config_unknown_field_names_key() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    std::io::Write::write_all(&mut temp_file, br#"{ "sample_rte": 16000 }"#).unwrap();
+
+    let error = Config::from_file(temp_file.path().to_str().unwrap()).unwrap_err().to_string();
+    assert!(error.contains("sample_rte"), "{}", error);
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let toml_path = temp_dir.path().join("config.toml");
+    fs::write(&toml_path, "[output_settings]\nprecison = 3\n").unwrap();
+
+    let error = Config::from_file(toml_path.to_str().unwrap()).unwrap_err().to_string();
+    assert!(error.contains("precison"), "{}", error);
+    assert!(error.contains("config.toml"), "{}", error);
+}
+
+#[test]
+fn test_config_semantic_validation() {
+    let cases = [
+        ("sample_rate = 0", "sample_rate"),
+        ("channels = 9", "channels"),
+        ("gain = 120.0", "gain"),
+        ("[[filters]]\nfilter_type = \"LowPass\"\ncutoff = -5.0", "filters[0].cutoff"),
+        ("[features]\nkind = \"Stft\"\nfft_size = 300", "features"),
+    ];
+
+    for (content, key) in cases {
+        let error = Config::parse(content, ConfigFormat::Toml).unwrap_err().to_string();
+        assert!(error.contains(key), "{}: {}", content, error);
+    }
+}
+
 #[test]
 fn test_write_to_readonly_directory() {
     let temp_dir = tempfile::TempDir::new().unwrap();
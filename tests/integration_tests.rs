@@ -1,4 +1,7 @@
 use audio_converter::*;
+use clap::Parser;
+use rstest::rstest;
+use serde_json::{json, Value};
 use std::fs;
 use std::io::Write;
 use tempfile::{NamedTempFile, TempDir};
@@ -68,6 +71,306 @@ fn test_config_file_integration() {
     assert!(config.normalize);
 }
 
+#[test]
+fn test_config_file_extended_formats() {
+    for (name, expected) in [
+        ("U8", OutputFormat::U8),
+        ("i24-packed", OutputFormat::I24Packed),
+        ("q15", OutputFormat::Q15),
+        ("Q31", OutputFormat::Q31),
+    ] {
+        let config_content = format!(
+            r#"{{
+                "output_format": "{}",
+                "sample_rate": null,
+                "channels": null,
+                "gain": 0.0,
+                "normalize": false,
+                "output_settings": {{
+                    "array_type": "Vec",
+                    "include_metadata": true,
+                    "compress": false
+                }}
+            }}"#,
+            name
+        );
+
+        let mut config_file = NamedTempFile::new().unwrap();
+        config_file.write_all(config_content.as_bytes()).unwrap();
+
+        let config = Config::from_file(config_file.path().to_str().unwrap()).unwrap();
+        assert_eq!(config.output_format, expected);
+    }
+}
+
+#[test]
+fn test_config_file_with_filters() {
+    let config_content = r#"
+    {
+        "output_format": "F32",
+        "sample_rate": null,
+        "channels": null,
+        "gain": 0.0,
+        "normalize": false,
+        "filters": [
+            { "filter_type": "DcBlock" },
+            { "filter_type": "HighPass", "cutoff": 80.0, "q": 0.5 },
+            { "filter_type": "HighShelf", "cutoff": 6000.0, "gain_db": -3.0 }
+        ],
+        "output_settings": {
+            "array_type": "Vec",
+            "include_metadata": true,
+            "compress": false
+        }
+    }
+    "#;
+
+    let mut config_file = NamedTempFile::new().unwrap();
+    config_file.write_all(config_content.as_bytes()).unwrap();
+
+    let config = Config::from_file(config_file.path().to_str().unwrap()).unwrap();
+    assert_eq!(config.filters.len(), 3);
+    assert_eq!(config.filters[0].cutoff, None);
+    assert_eq!(config.filters[1].cutoff, Some(80.0));
+    assert_eq!(config.filters[1].q, 0.5);
+    assert_eq!(config.filters[2].gain_db, -3.0);
+
+    let converter = AudioConverter::new(config);
+    let converted = converter.convert(&[0.1, 0.2, 0.3, 0.4], 44100, 2).unwrap();
+    assert_eq!(converted.samples.len(), 4);
+}
+
+#[test]
+fn test_config_file_with_processing_chain() {
+    let config_content = r#"
+    {
+        "output_format": "F32",
+        "sample_rate": 22050,
+        "channels": 1,
+        "gain": 6.0,
+        "normalize": false,
+        "processing_chain": [
+            { "type": "Channels" },
+            { "type": "Resample" },
+            { "type": "Normalize" },
+            { "type": "Gain", "db": -6.0 }
+        ],
+        "output_settings": {
+            "array_type": "Vec",
+            "include_metadata": true,
+            "compress": false
+        }
+    }
+    "#;
+
+    let mut config_file = NamedTempFile::new().unwrap();
+    config_file.write_all(config_content.as_bytes()).unwrap();
+
+    let config = Config::from_file(config_file.path().to_str().unwrap()).unwrap();
+    let converter = AudioConverter::new(config);
+    assert_eq!(
+        converter.stage_names(),
+        vec!["channels", "resample", "normalize", "gain"]
+    );
+
+    let test_samples: Vec<f32> = (0..441).flat_map(|i| {
+        let s = (i as f32 * 0.1).sin() * 0.25;
+        [s, s]
+    }).collect();
+    let converted = converter.convert(&test_samples, 44100, 2).unwrap();
+
+    assert_eq!(converted.channels, 1);
+    assert_eq!(converted.sample_rate, 22050);
+    let peak = converted.samples.iter().fold(0.0_f32, |m, s| m.max(s.abs()));
+    assert!((peak - 10.0_f32.powf(-6.0 / 20.0)).abs() < 1e-4);
+}
+
+#[test]
+fn test_config_file_toml_and_yaml() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let toml_path = temp_dir.path().join("config.toml");
+    fs::write(
+        &toml_path,
+        r#"
+output_format = "i16"
+sample_rate = 16000
+channels = 1
+
+[output_settings]
+layout = "planar"
+values_per_line = 8
+wav_format = "float32"
+
+[[filters]]
+filter_type = "HighPass"
+cutoff = 80.0
+"#,
+    )
+    .unwrap();
+    let config = Config::from_file(toml_path.to_str().unwrap()).unwrap();
+    assert_eq!(config.output_format, OutputFormat::I16);
+    assert_eq!(config.sample_rate, Some(16000));
+    assert_eq!(config.output_settings.layout, ArrayLayout::Planar);
+    assert_eq!(config.output_settings.values_per_line, 8);
+    assert_eq!(config.output_settings.wav_format, Some(WavFormat::Float32));
+    assert_eq!(config.filters[0].cutoff, Some(80.0));
+
+    let yaml_path = temp_dir.path().join("config.yml");
+    fs::write(
+        &yaml_path,
+        "output_format: u8\nchannels: 2\ngain: -3.5\noutput_settings:\n  hex_integers: true\n  wav_format: pcm24\n",
+    )
+    .unwrap();
+    let config = Config::from_file(yaml_path.to_str().unwrap()).unwrap();
+    assert_eq!(config.output_format, OutputFormat::U8);
+    assert_eq!(config.channels, Some(2));
+    assert_eq!(config.gain, -3.5);
+    assert!(config.output_settings.hex_integers);
+    assert_eq!(config.output_settings.wav_format, Some(WavFormat::Pcm24));
+}
+
+#[test]
+fn test_partial_config_uses_defaults() {
+    let mut config_file = NamedTempFile::new().unwrap();
+    config_file.write_all(br#"{ "sample_rate": 8000 }"#).unwrap();
+
+    let config = Config::from_file(config_file.path().to_str().unwrap()).unwrap();
+    let defaults = Config::default();
+    assert_eq!(config.sample_rate, Some(8000));
+    assert_eq!(config.output_format, defaults.output_format);
+    assert_eq!(config.gain, 0.0);
+    assert!(config.filters.is_empty());
+    assert_eq!(config.output_settings.array_type, "Vec");
+    assert_eq!(config.output_settings.precision, 6);
+}
+
+/// 所有字段都不是默认值的配置，用于检查命令行与配置文件的优先级
+const FULL_CONFIG: &str = r#"
+output_format = "i16"
+sample_rate = 22050
+channels = 1
+gain = 3.0
+normalize = true
+processing_chain = [{ type = "Normalize" }, { type = "Gain" }]
+
+[[filters]]
+filter_type = "HighPass"
+cutoff = 80.0
+
+[features]
+kind = "Mel"
+fft_size = 1024
+hop = 256
+mel_bands = 40
+
+[output_settings]
+array_type = "Array"
+include_metadata = false
+compress = true
+wav_format = "Pcm24"
+layout = "Planar"
+float_format = "Shortest"
+precision = 3
+hex_integers = true
+values_per_line = 8
+template = "builtin:c-header"
+endianness = "Big"
+csv_timestamps = true
+name = "beep"
+"#;
+
+fn merged_config(cli: &[&str]) -> Value {
+    let mut config = Config::parse(FULL_CONFIG, ConfigFormat::Toml).unwrap();
+    let args = Args::try_parse_from(["audio-converter"].iter().chain(cli)).unwrap();
+    config.merge_with_args(&args).unwrap();
+    serde_json::to_value(&config).unwrap()
+}
+
+#[test]
+fn test_cli_omitted_keeps_config_values() {
+    let config = Config::parse(FULL_CONFIG, ConfigFormat::Toml).unwrap();
+    assert_eq!(merged_config(&[]), serde_json::to_value(&config).unwrap());
+}
+
+#[rstest]
+#[case(&["-f", "f32"], "/output_format", json!("F32"))]
+#[case(&["-s", "48000"], "/sample_rate", json!(48000))]
+#[case(&["-s", "source"], "/sample_rate", Value::Null)]
+#[case(&["-c", "2"], "/channels", json!(2))]
+#[case(&["-c", "none"], "/channels", Value::Null)]
+#[case(&["-g", "-6"], "/gain", json!(-6.0))]
+#[case(&["--no-normalize"], "/normalize", json!(false))]
+#[case(&["--filter", "highpass:1000"], "/filters/0/cutoff", json!(1000.0))]
+#[case(&["--no-filters"], "/filters", json!([]))]
+#[case(&["--chain", "gain,resample"], "/processing_chain", json!([{"type": "Gain", "db": null}, {"type": "Resample", "sample_rate": null}]))]
+#[case(&["--chain", "default"], "/processing_chain", Value::Null)]
+#[case(&["--features", "mfcc"], "/features/kind", json!("Mfcc"))]
+#[case(&["--fft-size", "2048"], "/features/fft_size", json!(2048))]
+#[case(&["--hop", "128"], "/features/hop", json!(128))]
+#[case(&["--window", "hamming"], "/features/window", json!("Hamming"))]
+#[case(&["--mel-bands", "64"], "/features/mel_bands", json!(64))]
+#[case(&["--mfcc", "20"], "/features/mfcc", json!(20))]
+#[case(&["--no-features"], "/features", Value::Null)]
+#[case(&["--array-type", "Vec"], "/output_settings/array_type", json!("Vec"))]
+#[case(&["--metadata"], "/output_settings/include_metadata", json!(true))]
+#[case(&["--no-compress"], "/output_settings/compress", json!(false))]
+#[case(&["--wav-format", "float32"], "/output_settings/wav_format", json!("Float32"))]
+#[case(&["--layout", "per-channel"], "/output_settings/layout", json!("PerChannel"))]
+#[case(&["--float-format", "fixed"], "/output_settings/float_format", json!("Fixed"))]
+#[case(&["--precision", "9"], "/output_settings/precision", json!(9))]
+#[case(&["--no-hex"], "/output_settings/hex_integers", json!(false))]
+#[case(&["--per-line", "1"], "/output_settings/values_per_line", json!(1))]
+#[case(&["--template", "my.tpl"], "/output_settings/template", json!("my.tpl"))]
+#[case(&["--no-template"], "/output_settings/template", Value::Null)]
+#[case(&["--endian", "little"], "/output_settings/endianness", json!("Little"))]
+#[case(&["--no-timestamps"], "/output_settings/csv_timestamps", json!(false))]
+#[case(&["--name", "click"], "/output_settings/name", json!("click"))]
+fn test_cli_overrides_config_field(#[case] cli: &[&str], #[case] pointer: &str, #[case] expected: Value) {
+    let merged = merged_config(cli);
+    assert_eq!(merged.pointer(pointer), Some(&expected), "{:?}", cli);
+
+    // 其他字段保持配置文件的值
+    let mut untouched = merged_config(&[]);
+    let mut changed = merged;
+    for value in [&mut untouched, &mut changed] {
+        let (parent, key) = pointer.rsplit_once('/').unwrap();
+        let parent = if parent.is_empty() { value } else { value.pointer_mut(parent).unwrap() };
+        parent.as_object_mut().unwrap().remove(key);
+    }
+    assert_eq!(changed, untouched, "{:?}", cli);
+}
+
+#[test]
+fn test_cli_enable_flags_override_config() {
+    let mut config = Config::parse(FULL_CONFIG, ConfigFormat::Toml).unwrap();
+    config.normalize = false;
+    config.output_settings.hex_integers = false;
+    config.output_settings.compress = false;
+    let args = Args::try_parse_from([
+        "audio-converter", "--normalize", "--hex", "--compress", "--no-metadata",
+        "--no-normalize", "--normalize",
+    ])
+    .unwrap();
+    config.merge_with_args(&args).unwrap();
+
+    // 同一对开关以最后出现的为准
+    assert!(config.normalize);
+    assert!(config.output_settings.hex_integers);
+    assert!(config.output_settings.compress);
+    assert!(!config.output_settings.include_metadata);
+}
+
+#[test]
+fn test_cli_invalid_override_values() {
+    assert!(Args::try_parse_from(["audio-converter", "-s", "fast"]).is_err());
+    assert!(Args::try_parse_from(["audio-converter", "--filter", "lowpass:1000", "--no-filters"]).is_err());
+
+    let mut config = Config::default();
+    let args = Args::try_parse_from(["audio-converter", "--chain", "gain,reverb"]).unwrap();
+    assert!(config.merge_with_args(&args).is_err());
+}
+
 #[test]
 fn test_multiple_format_conversions() {
     let temp_audio = create_test_wav_file(44100, 2, 0.1);
@@ -76,8 +379,15 @@ fn test_multiple_format_conversions() {
     let formats = vec![
         OutputFormat::F32,
         OutputFormat::F64,
+        OutputFormat::I8,
+        OutputFormat::U8,
         OutputFormat::I16,
+        OutputFormat::U16,
+        OutputFormat::I24,
+        OutputFormat::I24Packed,
         OutputFormat::I32,
+        OutputFormat::Q15,
+        OutputFormat::Q31,
     ];
 
     for format in formats {
@@ -103,12 +413,152 @@ fn test_multiple_format_conversions() {
 
         // 验证输出文件存在并包含正确格式
         let content = fs::read_to_string(&output_path).unwrap();
-        match format {
-            OutputFormat::F32 => assert!(content.contains("[f32;")),
-            OutputFormat::F64 => assert!(content.contains("[f64;")),
-            OutputFormat::I16 => assert!(content.contains("[i16;")),
-            OutputFormat::I32 => assert!(content.contains("[i32;")),
-        }
+        let expected_type = match format {
+            OutputFormat::F32 => "[f32;",
+            OutputFormat::F64 => "[f64;",
+            OutputFormat::I8 => "[i8;",
+            OutputFormat::U8 | OutputFormat::I24Packed => "[u8;",
+            OutputFormat::I16 | OutputFormat::Q15 => "[i16;",
+            OutputFormat::U16 => "[u16;",
+            OutputFormat::I24 | OutputFormat::I32 | OutputFormat::Q31 => "[i32;",
+        };
+        assert!(content.contains(expected_type), "{:?}", format);
+        assert!(content.contains(&format!("\"format\":\"{}\"", format.name())));
+    }
+}
+
+#[test]
+fn test_integer_passthrough_is_bit_exact() {
+    use audio_converter::cli::convert::{convert_file, ConvertJob, ConvertOptions};
+
+    // 低位不为零的 32 位样本经过 f32 会丢失精度
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.wav");
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate: 48000,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&input, spec).unwrap();
+    for i in 0..2000i32 {
+        writer.write_sample(i.wrapping_mul(0x0123_4567) | 1).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    let read = |path: &std::path::Path| -> Vec<i32> {
+        hound::WavReader::open(path).unwrap().samples().map(|s| s.unwrap()).collect()
+    };
+    let config = Config {
+        output_format: OutputFormat::I32,
+        channels: Some(2),
+        ..Default::default()
+    };
+
+    let job = ConvertJob {
+        input: input.clone(),
+        output: temp_dir.path().join("copy.wav"),
+    };
+    convert_file(&job, &config, &ConvertOptions::default()).unwrap();
+    assert_eq!(read(&job.output), read(&input));
+
+    let job = ConvertJob {
+        input: input.clone(),
+        output: temp_dir.path().join("copy.rs"),
+    };
+    convert_file(&job, &config, &ConvertOptions::default()).unwrap();
+    let decoded = ArrayReader::read_file(job.output.to_str().unwrap(), &ArrayParams::default()).unwrap();
+    assert_eq!(decoded.samples.len(), 2000);
+    let content = fs::read_to_string(&job.output).unwrap();
+    assert!(content.contains(&format!("{},", 0x0123_4567 | 1)), "{}", &content[..300]);
+}
+
+#[test]
+fn test_integer_passthrough_to_data_files_is_bit_exact() {
+    use audio_converter::cli::convert::{convert_file, ConvertJob, ConvertOptions};
+
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.wav");
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate: 48000,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&input, spec).unwrap();
+    let samples: Vec<i32> = (0..2000i32).map(|i| i.wrapping_mul(0x0123_4567) | 1).collect();
+    for &sample in &samples {
+        writer.write_sample(sample).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    let config = Config {
+        output_format: OutputFormat::I32,
+        ..Default::default()
+    };
+    let to_i32 = |bytes: &[u8]| -> Vec<i32> {
+        bytes.chunks_exact(4).map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect()
+    };
+
+    let job = ConvertJob {
+        input: input.clone(),
+        output: temp_dir.path().join("copy.raw"),
+    };
+    convert_file(&job, &config, &ConvertOptions::default()).unwrap();
+    assert_eq!(to_i32(&fs::read(&job.output).unwrap()), samples);
+
+    // NPY 头部长度记录在第 8-9 字节
+    let job = ConvertJob {
+        input,
+        output: temp_dir.path().join("copy.npy"),
+    };
+    convert_file(&job, &config, &ConvertOptions::default()).unwrap();
+    let npy = fs::read(&job.output).unwrap();
+    let header_len = u16::from_le_bytes([npy[8], npy[9]]) as usize;
+    assert!(String::from_utf8_lossy(&npy[10..10 + header_len]).contains("'<i4'"));
+    assert_eq!(to_i32(&npy[10 + header_len..]), samples);
+}
+
+#[test]
+fn test_decode_time_channel_drop_matches_full_decode() {
+    // 6 声道源：解码时丢弃多余声道与完整解码后再转换的结果相同
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("surround.wav");
+    let spec = hound::WavSpec {
+        channels: 6,
+        sample_rate: 48000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&input, spec).unwrap();
+    for i in 0..6000i32 {
+        writer.write_sample(((i * 37) % 20000 - 10000) as i16).unwrap();
+    }
+    writer.finalize().unwrap();
+    let path = input.to_str().unwrap();
+
+    let mut full = AudioDecoder::new();
+    full.decode_file(path).unwrap();
+
+    for channels in [1, 2, 4] {
+        let converter = AudioConverter::new(Config {
+            channels: Some(channels),
+            ..Default::default()
+        });
+        let max_channels = converter.max_input_channels();
+        assert!(max_channels.is_some());
+
+        let mut dropped = AudioDecoder::new();
+        let options = DecodeOptions { native: false, max_channels, quiet: true };
+        dropped.decode_file_with(path, &options).unwrap();
+        assert!(dropped.get_channels() < 6);
+
+        let expected = converter.convert(full.get_samples(), 48000, 6).unwrap();
+        let actual = converter
+            .convert(dropped.get_samples(), 48000, dropped.get_channels())
+            .unwrap();
+        assert_eq!(actual.channels, channels);
+        assert_eq!(actual.samples, expected.samples, "-c {}", channels);
     }
 }
 
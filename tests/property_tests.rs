@@ -32,8 +32,15 @@ fn test_audio_decoding_properties(
 
 #[test_case(OutputFormat::F32)]
 #[test_case(OutputFormat::F64)]
+#[test_case(OutputFormat::I8)]
+#[test_case(OutputFormat::U8)]
 #[test_case(OutputFormat::I16)]
+#[test_case(OutputFormat::U16)]
+#[test_case(OutputFormat::I24)]
+#[test_case(OutputFormat::I24Packed)]
 #[test_case(OutputFormat::I32)]
+#[test_case(OutputFormat::Q15)]
+#[test_case(OutputFormat::Q31)]
 fn test_output_format_conversion(format: OutputFormat) {
     let test_samples = vec![0.0, 0.5, -0.5, 1.0, -1.0];
     let mut config = Config::default();